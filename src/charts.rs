@@ -0,0 +1,268 @@
+use tiny_skia::{Paint, PathBuilder, Pixmap, Rect, Stroke, Transform};
+
+const CHART_WIDTH: u32 = 800;
+const CHART_HEIGHT: u32 = 400;
+const CARD_WIDTH: u32 = 600;
+
+const BACKGROUND: (u8, u8, u8) = (47, 49, 54);
+const GRID: (u8, u8, u8) = (64, 68, 75);
+const TEXT: (u8, u8, u8) = (220, 221, 222);
+const MUTED_TEXT: (u8, u8, u8) = (142, 146, 151);
+const LINE: (u8, u8, u8) = (88, 101, 242);
+
+/// 5x7 bitmap glyphs, each row uses the low 5 bits from left to right
+fn get_glyph(c: char) -> [u8; 7] {
+    match c.to_ascii_uppercase() {
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '+' => [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '/' => [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+        '(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        ')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        '%' => [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03],
+        '_' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F],
+        ' ' => [0x00; 7],
+        _ => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+    }
+}
+
+fn get_paint(color: (u8, u8, u8)) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(color.0, color.1, color.2, 255);
+    paint.anti_alias = true;
+    paint
+}
+
+fn get_text_width(text: &str, scale: f32) -> f32 {
+    (text.chars().count() as f32 * 6.0 - 1.0).max(0.0) * scale
+}
+
+fn draw_text(pixmap: &mut Pixmap, text: &str, x: f32, y: f32, scale: f32, color: (u8, u8, u8)) {
+    let mut paint = get_paint(color);
+    paint.anti_alias = false;
+
+    for (idx, c) in text.chars().enumerate() {
+        let glyph_x = x + idx as f32 * 6.0 * scale;
+        for (row, bits) in get_glyph(c).iter().enumerate() {
+            for col in 0..5 {
+                if bits & (0x10 >> col) != 0 {
+                    if let Some(rect) = Rect::from_xywh(
+                        glyph_x + col as f32 * scale,
+                        y + row as f32 * scale,
+                        scale,
+                        scale,
+                    ) {
+                        pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Shortens large numbers for axis labels, such as `12.5M`
+pub fn format_compact(value: f64) -> String {
+    let abs = value.abs();
+    if abs >= 1_000_000_000.0 {
+        format!("{:.1}B", value / 1_000_000_000.0)
+    } else if abs >= 1_000_000.0 {
+        format!("{:.1}M", value / 1_000_000.0)
+    } else if abs >= 10_000.0 {
+        format!("{:.1}K", value / 1_000.0)
+    } else if value.fract() == 0.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+/// Renders a line chart of labelled points as a PNG
+pub fn render_line_chart(title: &str, points: &[(String, f64)]) -> Option<Vec<u8>> {
+    if points.is_empty() {
+        return None;
+    }
+
+    let mut pixmap = Pixmap::new(CHART_WIDTH, CHART_HEIGHT)?;
+    pixmap.fill(tiny_skia::Color::from_rgba8(
+        BACKGROUND.0,
+        BACKGROUND.1,
+        BACKGROUND.2,
+        255,
+    ));
+
+    let left = 100.0;
+    let right = CHART_WIDTH as f32 - 30.0;
+    let top = 60.0;
+    let bottom = CHART_HEIGHT as f32 - 50.0;
+
+    let mut min = points
+        .iter()
+        .map(|(_, value)| *value)
+        .fold(f64::INFINITY, f64::min);
+    let mut max = points
+        .iter()
+        .map(|(_, value)| *value)
+        .fold(f64::NEG_INFINITY, f64::max);
+    if (max - min).abs() < f64::EPSILON {
+        let padding = (max.abs() * 0.1).max(1.0);
+        min -= padding;
+        max += padding;
+    }
+
+    draw_text(&mut pixmap, title, 20.0, 18.0, 3.0, TEXT);
+
+    // Horizontal grid lines with value labels
+    let grid_paint = get_paint(GRID);
+    let grid_stroke = Stroke {
+        width: 1.0,
+        ..Stroke::default()
+    };
+    for step in 0..=4 {
+        let y = bottom - (bottom - top) * step as f32 / 4.0;
+        let mut pb = PathBuilder::new();
+        pb.move_to(left, y);
+        pb.line_to(right, y);
+        if let Some(path) = pb.finish() {
+            pixmap.stroke_path(
+                &path,
+                &grid_paint,
+                &grid_stroke,
+                Transform::identity(),
+                None,
+            );
+        }
+
+        let label = format_compact(min + (max - min) * step as f64 / 4.0);
+        draw_text(
+            &mut pixmap,
+            &label,
+            left - 10.0 - get_text_width(&label, 2.0),
+            y - 7.0,
+            2.0,
+            MUTED_TEXT,
+        );
+    }
+
+    let get_x = |idx: usize| {
+        if points.len() == 1 {
+            (left + right) / 2.0
+        } else {
+            left + (right - left) * idx as f32 / (points.len() - 1) as f32
+        }
+    };
+    let get_y = |value: f64| bottom - (bottom - top) * ((value - min) / (max - min)) as f32;
+
+    let line_paint = get_paint(LINE);
+    let mut pb = PathBuilder::new();
+    for (idx, (_, value)) in points.iter().enumerate() {
+        if idx == 0 {
+            pb.move_to(get_x(idx), get_y(*value));
+        } else {
+            pb.line_to(get_x(idx), get_y(*value));
+        }
+    }
+    if let Some(path) = pb.finish() {
+        pixmap.stroke_path(
+            &path,
+            &line_paint,
+            &Stroke {
+                width: 3.0,
+                ..Stroke::default()
+            },
+            Transform::identity(),
+            None,
+        );
+    }
+    for (idx, (_, value)) in points.iter().enumerate() {
+        if let Some(path) = PathBuilder::from_circle(get_x(idx), get_y(*value), 4.0) {
+            pixmap.fill_path(
+                &path,
+                &line_paint,
+                tiny_skia::FillRule::Winding,
+                Transform::identity(),
+                None,
+            );
+        }
+    }
+
+    // Label the first, middle and last points so labels never overlap
+    let mut label_idxs = vec![0, points.len() / 2, points.len() - 1];
+    label_idxs.dedup();
+    for idx in label_idxs {
+        let label = &points[idx].0;
+        let width = get_text_width(label, 2.0);
+        let x = (get_x(idx) - width / 2.0).clamp(left - 20.0, right - width);
+        draw_text(&mut pixmap, label, x, bottom + 16.0, 2.0, MUTED_TEXT);
+    }
+
+    pixmap.encode_png().ok()
+}
+
+/// Renders a card with a title and label/value rows as a PNG
+pub fn render_stat_card(title: &str, rows: &[(String, String)]) -> Option<Vec<u8>> {
+    let mut pixmap = Pixmap::new(CARD_WIDTH, 90 + rows.len() as u32 * 40)?;
+    pixmap.fill(tiny_skia::Color::from_rgba8(
+        BACKGROUND.0,
+        BACKGROUND.1,
+        BACKGROUND.2,
+        255,
+    ));
+
+    if let Some(rect) = Rect::from_xywh(0.0, 0.0, 8.0, pixmap.height() as f32) {
+        pixmap.fill_rect(rect, &get_paint(LINE), Transform::identity(), None);
+    }
+    draw_text(&mut pixmap, title, 30.0, 25.0, 3.0, TEXT);
+
+    for (idx, (label, value)) in rows.iter().enumerate() {
+        let y = 80.0 + idx as f32 * 40.0;
+        draw_text(&mut pixmap, label, 30.0, y, 2.0, MUTED_TEXT);
+        draw_text(
+            &mut pixmap,
+            value,
+            CARD_WIDTH as f32 - 30.0 - get_text_width(value, 2.0),
+            y,
+            2.0,
+            TEXT,
+        );
+    }
+
+    pixmap.encode_png().ok()
+}
@@ -63,8 +63,198 @@ impl Config {
             "CREATE TABLE IF NOT EXISTS linked_accounts (
                 uuid TEXT PRIMARY KEY,
                 username TEXT UNIQUE,
-                discord TEXT UNIQUE,
-                last_updated BIGINT
+                discord TEXT,
+                last_updated BIGINT,
+                is_primary BOOLEAN NOT NULL DEFAULT TRUE
+            )",
+        )
+        .await?;
+        // Discord users used to be limited to a single account
+        pool.simple_query(
+            "ALTER TABLE linked_accounts ADD COLUMN IF NOT EXISTS is_primary BOOLEAN NOT NULL DEFAULT TRUE",
+        )
+        .await?;
+        pool.simple_query(
+            "ALTER TABLE linked_accounts DROP CONSTRAINT IF EXISTS linked_accounts_discord_key",
+        )
+        .await?;
+        pool.simple_query(
+            "CREATE UNIQUE INDEX IF NOT EXISTS linked_accounts_primary ON linked_accounts (discord) WHERE is_primary",
+        )
+        .await?;
+        pool.simple_query(
+            "CREATE TABLE IF NOT EXISTS archived_accounts (
+                uuid TEXT,
+                username TEXT,
+                discord TEXT,
+                last_updated BIGINT,
+                archived_at BIGINT
+            )",
+        )
+        .await?;
+        pool.simple_query(
+            "CREATE TABLE IF NOT EXISTS link_history (
+                id serial NOT NULL PRIMARY KEY,
+                uuid TEXT,
+                username TEXT,
+                discord TEXT,
+                action TEXT,
+                time BIGINT
+            )",
+        )
+        .await?;
+        pool.simple_query(
+            "CREATE TABLE IF NOT EXISTS name_history (
+                id serial NOT NULL PRIMARY KEY,
+                uuid TEXT,
+                old_username TEXT,
+                new_username TEXT,
+                time BIGINT
+            )",
+        )
+        .await?;
+        pool.simple_query(
+            "CREATE TABLE IF NOT EXISTS guild_members (
+                guild_id TEXT,
+                uuid TEXT,
+                rank TEXT,
+                joined BIGINT,
+                PRIMARY KEY (guild_id, uuid)
+            )",
+        )
+        .await?;
+        pool.simple_query(
+            "CREATE TABLE IF NOT EXISTS guild_exp (
+                guild_id TEXT,
+                uuid TEXT,
+                date TEXT,
+                exp BIGINT,
+                PRIMARY KEY (guild_id, uuid, date)
+            )",
+        )
+        .await?;
+        pool.simple_query(
+            "CREATE TABLE IF NOT EXISTS task_runs (
+                name TEXT PRIMARY KEY,
+                time BIGINT
+            )",
+        )
+        .await?;
+        pool.simple_query(
+            "CREATE TABLE IF NOT EXISTS req_sweep (
+                guild_id TEXT,
+                uuid TEXT,
+                failing TEXT,
+                time BIGINT,
+                PRIMARY KEY (guild_id, uuid)
+            )",
+        )
+        .await?;
+        pool.simple_query(
+            "CREATE TABLE IF NOT EXISTS req_warnings (
+                guild_id TEXT,
+                uuid TEXT,
+                failing TEXT,
+                warned_at BIGINT,
+                deadline BIGINT,
+                PRIMARY KEY (guild_id, uuid)
+            )",
+        )
+        .await?;
+        pool.simple_query(
+            "CREATE TABLE IF NOT EXISTS applications (
+                id SERIAL PRIMARY KEY,
+                guild_id TEXT,
+                guild_name TEXT,
+                uuid TEXT,
+                discord TEXT,
+                thread TEXT,
+                status TEXT,
+                reviewer TEXT,
+                created BIGINT,
+                decided BIGINT
+            )",
+        )
+        .await?;
+        pool.simple_query(
+            "CREATE TABLE IF NOT EXISTS waitlist (
+                id SERIAL PRIMARY KEY,
+                guild_id TEXT,
+                uuid TEXT,
+                discord TEXT,
+                added BIGINT,
+                notified BIGINT,
+                UNIQUE (guild_id, uuid)
+            )",
+        )
+        .await?;
+        pool.simple_query(
+            "CREATE TABLE IF NOT EXISTS player_stats (
+                uuid TEXT PRIMARY KEY,
+                username TEXT,
+                slayer BIGINT,
+                skills DOUBLE PRECISION,
+                catacombs DOUBLE PRECISION,
+                weight DOUBLE PRECISION,
+                level DOUBLE PRECISION,
+                updated BIGINT
+            )",
+        )
+        .await?;
+        pool.simple_query(
+            "CREATE TABLE IF NOT EXISTS stat_snapshots (
+                uuid TEXT,
+                time BIGINT,
+                slayer BIGINT,
+                skills DOUBLE PRECISION,
+                catacombs DOUBLE PRECISION,
+                weight DOUBLE PRECISION,
+                level DOUBLE PRECISION,
+                PRIMARY KEY (uuid, time)
+            )",
+        )
+        .await?;
+        pool.simple_query(
+            "CREATE TABLE IF NOT EXISTS player_status (
+                uuid TEXT PRIMARY KEY,
+                online BOOLEAN NOT NULL,
+                game TEXT,
+                mode TEXT,
+                map TEXT,
+                updated BIGINT
+            )",
+        )
+        .await?;
+        pool.simple_query(
+            "CREATE TABLE IF NOT EXISTS player_logins (
+                uuid TEXT PRIMARY KEY,
+                username TEXT,
+                last_login BIGINT,
+                updated BIGINT
+            )",
+        )
+        .await?;
+        pool.simple_query(
+            "CREATE TABLE IF NOT EXISTS competitions (
+                id SERIAL PRIMARY KEY,
+                stat TEXT,
+                guild_id TEXT,
+                guild_name TEXT,
+                channel TEXT,
+                start_time BIGINT,
+                end_time BIGINT,
+                updated BIGINT,
+                ended BOOLEAN NOT NULL DEFAULT FALSE
+            )",
+        )
+        .await?;
+        pool.simple_query(
+            "CREATE TABLE IF NOT EXISTS competition_entries (
+                competition_id INTEGER,
+                uuid TEXT,
+                start_value DOUBLE PRECISION,
+                current_value DOUBLE PRECISION,
+                PRIMARY KEY (competition_id, uuid)
             )",
         )
         .await?;
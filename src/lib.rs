@@ -1,3 +1,4 @@
+pub mod charts;
 pub mod config;
 pub mod structs;
 pub mod utils;
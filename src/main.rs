@@ -1,27 +1,72 @@
 use bot::{
+    charts::{format_compact, render_line_chart, render_stat_card},
     config::Config,
-    structs::{CommandOptionBuilder, ServerConfig},
-    utils::{default_embed, get_discord_info, get_timestamp_millis, SELF_USER_ID},
+    structs::{
+        CommandOptionBuilder, GuildReqs, LeavePolicy, LinkedAccount, PlayerLogin, PlayerStats,
+        PlayerStatus, ServerConfig, StatRole,
+    },
+    utils::{
+        default_embed, format_date, get_competition_stats, get_competition_value, get_discord_info,
+        get_dungeon_levels, get_guild_level, get_player_stats, get_skill_level, get_skill_names,
+        get_timestamp_millis, normalize_rank, parse_amount, parse_duration, GUILD_MEMBER_LIMIT,
+        SELF_USER_ID,
+    },
 };
+use deadpool_postgres::Object;
 use futures::stream::StreamExt;
+use rs_pixel::{
+    response::guild_response::Guild,
+    types::server_type::ServerType,
+    util::{generic_json::Property, skyblock_profile::SkyblockProfile},
+};
 use std::fmt::Write;
-use std::{error::Error, str::FromStr, sync::Arc};
-use tokio::sync::Mutex;
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    error::Error,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::{Mutex, MutexGuard};
 use twilight_gateway::{Cluster, Event};
 use twilight_http::Client as HttpClient;
 use twilight_model::{
     application::{
-        command::CommandOption,
+        command::{
+            CommandOption, CommandOptionValue as CommandOptionValueType, NumberCommandOptionData,
+            OptionsCommandOptionData,
+        },
+        component::{
+            button::ButtonStyle, text_input::TextInputStyle, ActionRow, Button, Component,
+            TextInput,
+        },
         interaction::{
             application_command::{CommandData, CommandOptionValue},
+            message_component::MessageComponentInteractionData,
+            modal::ModalInteractionData,
             InteractionData,
         },
     },
-    gateway::{payload::incoming::InteractionCreate, Intents},
-    http::interaction::{InteractionResponse, InteractionResponseType},
-    id::Id,
+    channel::{embed::Embed, message::MessageFlags, ChannelType},
+    gateway::{
+        payload::incoming::{InteractionCreate, MemberAdd, MemberRemove},
+        Intents,
+    },
+    guild::{Member, Permissions},
+    http::{
+        attachment::Attachment,
+        interaction::{InteractionResponse, InteractionResponseType},
+    },
+    id::{
+        marker::{ChannelMarker, GuildMarker, RoleMarker, UserMarker},
+        Id,
+    },
+};
+use twilight_util::builder::{
+    embed::{EmbedBuilder, EmbedFieldBuilder, ImageSource},
+    InteractionResponseDataBuilder,
 };
-use twilight_util::builder::embed::EmbedFieldBuilder;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -30,7 +75,9 @@ async fn main() -> anyhow::Result<()> {
 
     let (cluster, mut events) = Cluster::new(
         config.bot_token.clone(),
-        Intents::GUILD_MESSAGES.union(Intents::MESSAGE_CONTENT),
+        Intents::GUILD_MESSAGES
+            .union(Intents::MESSAGE_CONTENT)
+            .union(Intents::GUILD_MEMBERS),
     )
     .await?;
     let cluster = Arc::new(cluster);
@@ -58,6 +105,19 @@ async fn main() -> anyhow::Result<()> {
 
     let config_clone = Arc::new(Mutex::new(config));
 
+    tokio::spawn(run_background_sync(
+        Arc::clone(&http),
+        Arc::clone(&config_clone),
+    ));
+    tokio::spawn(run_requirement_sweep(
+        Arc::clone(&http),
+        Arc::clone(&config_clone),
+    ));
+    tokio::spawn(run_competition_updates(
+        Arc::clone(&http),
+        Arc::clone(&config_clone),
+    ));
+
     while let Some((shard_id, event)) = events.next().await {
         // cache.update(&event);
 
@@ -82,8 +142,28 @@ async fn register_commands(
         .interaction(self_user_id)
         .create_guild_command(config.guild_id)
         .chat_input("verify", "Link your Hypixel account")?
+        .command_options(&[
+            CommandOption::String(
+                CommandOptionBuilder::new("player", "Your in-game username")
+                    .set_required(true)
+                    .into(),
+            ),
+            CommandOption::Boolean(
+                CommandOptionBuilder::new("alt", "Link as an additional account").into(),
+            ),
+            CommandOption::Boolean(
+                CommandOptionBuilder::new("primary", "Make this your primary account").into(),
+            ),
+        ])?
+        .exec()
+        .await;
+
+    let _ = http
+        .interaction(self_user_id)
+        .create_guild_command(config.guild_id)
+        .chat_input("primary", "Switch your primary account")?
         .command_options(&[CommandOption::String(
-            CommandOptionBuilder::new("player", "Your in-game username")
+            CommandOptionBuilder::new("player", "One of your linked accounts")
                 .set_required(true)
                 .into(),
         )])?
@@ -92,9 +172,19 @@ async fn register_commands(
 
     // settings view
     // settings verified_role <@role>
+    // settings verify_panel <#channel>
+    // settings unverified_role <@role>
     // settings guild_role <guild> <@role>
+    // settings rank_role <guild>, <rank> <@role>
+    // settings rank_role clear <guild>
     // settings reqs remove <guild> type
     // settings reqs set <guild> <type> <amount>
+    // settings sync_nickname <true|false>
+    // settings rejoin_reqs <true|false>
+    // settings leave_policy <keep|archive|delete>
+    // settings log_channel <#channel>
+    // settings guild_log_channel <#channel>
+    // settings announce_renames <true|false>
     let _ = http
         .interaction(self_user_id)
         .create_guild_command(config.guild_id)
@@ -110,7 +200,7 @@ async fn register_commands(
     let _ = http
         .interaction(self_user_id)
         .create_guild_command(config.guild_id)
-        .chat_input("user", "See what account a user is linked to")?
+        .chat_input("user", "See what accounts a user is linked to")?
         .command_options(&[CommandOption::User(
             CommandOptionBuilder::new("user", "Discord user")
                 .set_required(true)
@@ -119,25 +209,276 @@ async fn register_commands(
         .exec()
         .await;
 
+    let _ = http
+        .interaction(self_user_id)
+        .create_guild_command(config.guild_id)
+        .chat_input("whois", "See what user a player is linked to")?
+        .command_options(&[CommandOption::String(
+            CommandOptionBuilder::new("player", "Player username or UUID")
+                .set_required(true)
+                .into(),
+        )])?
+        .exec()
+        .await;
+
+    let _ = http
+        .interaction(self_user_id)
+        .create_guild_command(config.guild_id)
+        .chat_input("history", "See the link history of a user or player")?
+        .command_options(&[
+            CommandOption::User(CommandOptionBuilder::new("user", "Discord user").into()),
+            CommandOption::String(CommandOptionBuilder::new("player", "Player username").into()),
+        ])?
+        .exec()
+        .await;
+
     let _ = http
         .interaction(self_user_id)
         .create_guild_command(config.guild_id)
         .chat_input("reqs", "Check if a player meets the requirements")?
         .command_options(&[
-            CommandOption::User(
+            CommandOption::String(
+                CommandOptionBuilder::new("player", "Player username")
+                    .set_required(true)
+                    .into(),
+            ),
+            CommandOption::String(CommandOptionBuilder::new("profile", "Profile name").into()),
+        ])?
+        .exec()
+        .await;
+
+    let _ = http
+        .interaction(self_user_id)
+        .create_guild_command(config.guild_id)
+        .chat_input(
+            "reconcile",
+            "Fix members with both or neither of the verified and unverified roles",
+        )?
+        .exec()
+        .await;
+
+    let _ = http
+        .interaction(self_user_id)
+        .create_guild_command(config.guild_id)
+        .chat_input("gexp", "See the guild experience of a guild or player")?
+        .command_options(&[CommandOption::String(
+            CommandOptionBuilder::new("query", "Guild name or player username")
+                .set_required(true)
+                .into(),
+        )])?
+        .exec()
+        .await;
+
+    let _ = http
+        .interaction(self_user_id)
+        .create_guild_command(config.guild_id)
+        .chat_input(
+            "kicklist",
+            "List inactive members and members failing requirements",
+        )?
+        .command_options(&[
+            CommandOption::String(
+                CommandOptionBuilder::new("guild", "Guild name")
+                    .set_required(true)
+                    .into(),
+            ),
+            CommandOption::Boolean(CommandOptionBuilder::new("csv", "Export as a CSV file").into()),
+        ])?
+        .exec()
+        .await;
+
+    let _ = http
+        .interaction(self_user_id)
+        .create_guild_command(config.guild_id)
+        .chat_input("warnings", "List members in a requirement grace period")?
+        .command_options(&[CommandOption::String(
+            CommandOptionBuilder::new("guild", "Guild name").into(),
+        )])?
+        .exec()
+        .await;
+
+    let _ = http
+        .interaction(self_user_id)
+        .create_guild_command(config.guild_id)
+        .chat_input("apply", "Apply to join a guild")?
+        .command_options(&[CommandOption::String(
+            CommandOptionBuilder::new("guild", "Guild name")
+                .set_required(true)
+                .into(),
+        )])?
+        .exec()
+        .await;
+
+    let _ = http
+        .interaction(self_user_id)
+        .create_guild_command(config.guild_id)
+        .chat_input("waitlist", "See the waitlist of a full guild")?
+        .command_options(&[CommandOption::String(
+            CommandOptionBuilder::new("guild", "Guild name").into(),
+        )])?
+        .exec()
+        .await;
+
+    let _ = http
+        .interaction(self_user_id)
+        .create_guild_command(config.guild_id)
+        .chat_input("leaderboard", "See the top linked members or guild members")?
+        .command_options(&[
+            CommandOption::String(
+                LEADERBOARD_STATS
+                    .iter()
+                    .fold(
+                        CommandOptionBuilder::new("stat", "Stat to rank by"),
+                        |builder, (stat, name)| builder.add_choice(*name, *stat),
+                    )
+                    .set_required(true)
+                    .into(),
+            ),
+            CommandOption::String(CommandOptionBuilder::new("guild", "Guild name").into()),
+        ])?
+        .exec()
+        .await;
+
+    let _ = http
+        .interaction(self_user_id)
+        .create_guild_command(config.guild_id)
+        .chat_input("progress", "See a player's stat gains over time")?
+        .command_options(&[
+            CommandOption::String(
                 CommandOptionBuilder::new("player", "Player username")
                     .set_required(true)
                     .into(),
             ),
-            CommandOption::User(
-                CommandOptionBuilder::new("profile", "Profile name")
+            CommandOption::Integer(NumberCommandOptionData {
+                autocomplete: false,
+                choices: Vec::new(),
+                description: "Number of days to look back (default 7)".to_string(),
+                description_localizations: None,
+                max_value: Some(CommandOptionValueType::Integer(365)),
+                min_value: Some(CommandOptionValueType::Integer(1)),
+                name: "days".to_string(),
+                name_localizations: None,
+                required: false,
+            }),
+        ])?
+        .exec()
+        .await;
+
+    let _ = http
+        .interaction(self_user_id)
+        .create_guild_command(config.guild_id)
+        .chat_input("guild", "See an overview of a Hypixel guild")?
+        .command_options(&[CommandOption::String(
+            CommandOptionBuilder::new("guild", "Guild name or player username")
+                .set_required(true)
+                .into(),
+        )])?
+        .exec()
+        .await;
+
+    let _ = http
+        .interaction(self_user_id)
+        .create_guild_command(config.guild_id)
+        .chat_input("status", "See whether a player is online on Hypixel")?
+        .command_options(&[CommandOption::String(
+            CommandOptionBuilder::new("player", "Player username")
+                .set_required(true)
+                .into(),
+        )])?
+        .exec()
+        .await;
+
+    let _ = http
+        .interaction(self_user_id)
+        .create_guild_command(config.guild_id)
+        .chat_input(
+            "online",
+            "List the linked members of a guild who are online",
+        )?
+        .command_options(&[CommandOption::String(
+            CommandOptionBuilder::new("guild", "Guild name or player username")
+                .set_required(true)
+                .into(),
+        )])?
+        .exec()
+        .await;
+
+    let _ = http
+        .interaction(self_user_id)
+        .create_guild_command(config.guild_id)
+        .chat_input("compare", "Compare the stats of two players")?
+        .command_options(&[
+            CommandOption::String(
+                CommandOptionBuilder::new("player1", "First player username")
+                    .set_required(true)
+                    .into(),
+            ),
+            CommandOption::String(
+                CommandOptionBuilder::new("player2", "Second player username")
                     .set_required(true)
                     .into(),
             ),
+            CommandOption::String(
+                CommandOptionBuilder::new("group", "Stats to compare")
+                    .add_choice("Overview", "overview")
+                    .add_choice("Skills", "skills")
+                    .add_choice("Slayer", "slayer")
+                    .add_choice("Dungeons", "dungeons")
+                    .into(),
+            ),
+        ])?
+        .exec()
+        .await;
+
+    let mut competition_start: OptionsCommandOptionData =
+        CommandOptionBuilder::new("start", "Start a competition").into();
+    competition_start.options = vec![
+        CommandOption::String(
+            get_competition_stats()
+                .into_iter()
+                .fold(
+                    CommandOptionBuilder::new("stat", "Stat to compete in"),
+                    |builder, stat| builder.add_choice(stat, stat),
+                )
+                .set_required(true)
+                .into(),
+        ),
+        CommandOption::String(
+            CommandOptionBuilder::new("duration", "Duration such as 12h, 7d or 2w")
+                .set_required(true)
+                .into(),
+        ),
+        CommandOption::String(CommandOptionBuilder::new("guild", "Guild name").into()),
+    ];
+    let _ = http
+        .interaction(self_user_id)
+        .create_guild_command(config.guild_id)
+        .chat_input("competition", "Run stat competitions")?
+        .command_options(&[
+            CommandOption::SubCommand(competition_start),
+            CommandOption::SubCommand(
+                CommandOptionBuilder::new("leaderboard", "See the current competition").into(),
+            ),
+            CommandOption::SubCommand(
+                CommandOptionBuilder::new("end", "End the current competition now").into(),
+            ),
         ])?
         .exec()
         .await;
 
+    let mut roles_update: OptionsCommandOptionData =
+        CommandOptionBuilder::new("update", "Update a user's stat roles").into();
+    roles_update.options = vec![CommandOption::User(
+        CommandOptionBuilder::new("user", "Discord user").into(),
+    )];
+    let _ = http
+        .interaction(self_user_id)
+        .create_guild_command(config.guild_id)
+        .chat_input("roles", "Manage stat roles")?
+        .command_options(&[CommandOption::SubCommand(roles_update)])?
+        .exec()
+        .await;
+
     let _ = http
         .interaction(self_user_id)
         .create_guild_command(config.guild_id)
@@ -179,6 +520,9 @@ async fn handle_event(
                     "verify" => {
                         handle_verify_command(&http, config, &interaction, interaction_data).await
                     }
+                    "primary" => {
+                        handle_primary_command(&http, config, &interaction, interaction_data).await
+                    }
                     "settings" => {
                         handle_settings_command(&http, config, &interaction, interaction_data).await
                     }
@@ -191,6 +535,57 @@ async fn handle_event(
                     "reqs" => {
                         handle_reqs_command(&http, config, &interaction, interaction_data).await
                     }
+                    "reconcile" => {
+                        handle_reconcile_command(&http, config, &interaction, interaction_data)
+                            .await
+                    }
+                    "gexp" => {
+                        handle_gexp_command(&http, config, &interaction, interaction_data).await
+                    }
+                    "kicklist" => {
+                        handle_kicklist_command(&http, config, &interaction, interaction_data).await
+                    }
+                    "warnings" => {
+                        handle_warnings_command(&http, config, &interaction, interaction_data).await
+                    }
+                    "apply" => {
+                        handle_apply_command(&http, config, &interaction, interaction_data).await
+                    }
+                    "waitlist" => {
+                        handle_waitlist_command(&http, config, &interaction, interaction_data).await
+                    }
+                    "roles" => {
+                        handle_roles_command(&http, config, &interaction, interaction_data).await
+                    }
+                    "leaderboard" => {
+                        handle_leaderboard_command(&http, config, &interaction, interaction_data)
+                            .await
+                    }
+                    "progress" => {
+                        handle_progress_command(&http, config, &interaction, interaction_data).await
+                    }
+                    "competition" => {
+                        handle_competition_command(&http, config, &interaction, interaction_data)
+                            .await
+                    }
+                    "guild" => {
+                        handle_guild_command(&http, config, &interaction, interaction_data).await
+                    }
+                    "status" => {
+                        handle_status_command(&http, config, &interaction, interaction_data).await
+                    }
+                    "online" => {
+                        handle_online_command(&http, config, &interaction, interaction_data).await
+                    }
+                    "compare" => {
+                        handle_compare_command(&http, config, &interaction, interaction_data).await
+                    }
+                    "whois" => {
+                        handle_whois_command(&http, config, &interaction, interaction_data).await
+                    }
+                    "history" => {
+                        handle_history_command(&http, config, &interaction, interaction_data).await
+                    }
                     _ => {
                         handle_unknown_command(&http, config, &interaction, interaction_data).await
                     }
@@ -204,6 +599,71 @@ async fn handle_event(
                         .exec()
                         .await?;
                 }
+            } else if let InteractionData::MessageComponent(component_data) =
+                interaction.data.as_ref().unwrap()
+            {
+                let result = match component_data.custom_id.as_str() {
+                    "verify_panel" => handle_verify_panel_button(&http, &interaction).await,
+                    custom_id if custom_id.starts_with("leaderboard:") => {
+                        handle_leaderboard_button(&http, config, &interaction, component_data).await
+                    }
+                    custom_id if custom_id.starts_with("application_") => {
+                        handle_application_button(&http, config, &interaction, component_data).await
+                    }
+                    _ => Ok(()),
+                };
+
+                if let Err(err) = result {
+                    println!(
+                        "Error handling component {}: {}",
+                        component_data.custom_id, err
+                    );
+                }
+            } else if let InteractionData::ModalSubmit(modal_data) =
+                interaction.data.as_ref().unwrap()
+            {
+                let _ = http
+                    .interaction(SELF_USER_ID.lock().await.unwrap())
+                    .create_response(
+                        interaction.id,
+                        &interaction.token,
+                        &InteractionResponse {
+                            kind: InteractionResponseType::DeferredChannelMessageWithSource,
+                            data: Some(
+                                InteractionResponseDataBuilder::new()
+                                    .flags(MessageFlags::EPHEMERAL)
+                                    .build(),
+                            ),
+                        },
+                    )
+                    .exec()
+                    .await?;
+
+                let result = match modal_data.custom_id.as_str() {
+                    "verify_modal" => {
+                        handle_verify_modal(&http, config, &interaction, modal_data).await
+                    }
+                    _ => Ok(()),
+                };
+
+                if let Err(err) = result {
+                    let _ = http
+                        .interaction(SELF_USER_ID.lock().await.unwrap())
+                        .create_followup(&interaction.token)
+                        .embeds(&[default_embed("Error").description(err.to_string()).build()])?
+                        .exec()
+                        .await?;
+                }
+            }
+        }
+        Event::MemberAdd(member) => {
+            if let Err(err) = handle_member_add(&http, config, member).await {
+                println!("Error restoring link on rejoin: {}", err);
+            }
+        }
+        Event::MemberRemove(member) => {
+            if let Err(err) = handle_member_remove(&http, config, member).await {
+                println!("Error handling member leave: {}", err);
             }
         }
         _ => {}
@@ -212,29 +672,4173 @@ async fn handle_event(
     Ok(())
 }
 
-async fn handle_unknown_command(
+async fn run_background_sync(http: Arc<HttpClient>, config: Arc<Mutex<Config>>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(60 * 60));
+    loop {
+        interval.tick().await;
+
+        if let Err(err) = sync_usernames(&http, &config).await {
+            println!("Error syncing usernames: {}", err);
+        }
+        if let Err(err) = sync_guilds(&http, &config).await {
+            println!("Error syncing guilds: {}", err);
+        }
+        if let Err(err) = refresh_stats_cache(&config).await {
+            println!("Error refreshing stats cache: {}", err);
+        }
+        if let Err(err) = sync_stat_roles(&http, &config).await {
+            println!("Error syncing stat roles: {}", err);
+        }
+    }
+}
+
+async fn sync_stat_roles(
     http: &Arc<HttpClient>,
-    config: Arc<Mutex<Config>>,
-    interaction: &Box<InteractionCreate>,
-    interaction_data: &Box<CommandData>,
+    config: &Arc<Mutex<Config>>,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let _ = http
-        .interaction(SELF_USER_ID.lock().await.unwrap())
-        .create_followup(&interaction.token)
-        .embeds(&[default_embed("Error")
-            .description("Unknown Command")
-            .build()])?
-        .exec()
+    let (guild_id, server_config, accounts, cached_stats) = {
+        let config = config.lock().await;
+        let pool = config.database.get().await?;
+        let accounts: Vec<_> = pool
+            .query("SELECT * FROM linked_accounts", &[])
+            .await?
+            .iter()
+            .map(LinkedAccount::from_row)
+            .collect();
+        // Stats come from the cache refreshed right before rather than fetching every profile
+        let cached_stats: HashMap<String, PlayerStats> = pool
+            .query(
+                "SELECT * FROM player_stats WHERE uuid IN (SELECT uuid FROM linked_accounts)",
+                &[],
+            )
+            .await?
+            .iter()
+            .map(|db_res| (db_res.get("uuid"), PlayerStats::from_row(db_res)))
+            .collect();
+        (
+            config.guild_id,
+            ServerConfig::read_config(&pool).await,
+            accounts,
+            cached_stats,
+        )
+    };
+    if server_config.stat_roles.is_empty() {
+        return Ok(());
+    }
+
+    let mut stats_by_discord: HashMap<&str, Vec<PlayerStats>> = HashMap::new();
+    for account in &accounts {
+        if let Some(stats) = cached_stats.get(&account.uuid) {
+            stats_by_discord
+                .entry(account.discord.as_str())
+                .or_default()
+                .push(stats.clone());
+        }
+    }
+
+    for member in get_all_members(http, guild_id).await? {
+        if let Some(member_stats) = stats_by_discord.get(member.user.id.to_string().as_str()) {
+            if let Err(err) = apply_stat_roles(
+                http,
+                &server_config,
+                guild_id,
+                member.user.id,
+                member_stats,
+                &member.roles,
+            )
+            .await
+            {
+                println!("Error syncing stat roles of {}: {}", member.user.id, err);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_competition_updates(http: Arc<HttpClient>, config: Arc<Mutex<Config>>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(15 * 60));
+    loop {
+        interval.tick().await;
+
+        if let Err(err) = update_competitions(&http, &config).await {
+            println!("Error updating competitions: {}", err);
+        }
+    }
+}
+
+/// Called every 15 minutes, refreshes values that are over an hour old and announces the
+/// winners of finished competitions
+async fn update_competitions(
+    http: &Arc<HttpClient>,
+    config: &Arc<Mutex<Config>>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let competitions = {
+        let config = config.lock().await;
+        let pool = config.database.get().await?;
+        pool.query("SELECT * FROM competitions WHERE NOT ended", &[])
+            .await?
+    };
+
+    let now = get_timestamp_millis();
+    for competition in competitions {
+        let id: i32 = competition.get("id");
+        let updated: i64 = competition.get("updated");
+        let end_time: i64 = competition.get("end_time");
+        let finished = now >= end_time;
+        if finished || now - updated >= 60 * 60 * 1000 {
+            // Ending is retried on the next run so the final standings are up to date
+            if let Err(err) = refresh_competition(config, id, competition.get("stat")).await {
+                println!("Error refreshing competition {}: {}", id, err);
+                continue;
+            }
+        }
+
+        if finished {
+            if let Err(err) = end_competition(http, config, id).await {
+                println!("Error ending competition {}: {}", id, err);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn refresh_competition(
+    config: &Arc<Mutex<Config>>,
+    id: i32,
+    stat: &str,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let uuids: Vec<String> = {
+        let config = config.lock().await;
+        let pool = config.database.get().await?;
+        pool.query(
+            "SELECT uuid FROM competition_entries WHERE competition_id = $1",
+            &[&id],
+        )
+        .await?
+        .iter()
+        .map(|db_res| db_res.get("uuid"))
+        .collect()
+    };
+
+    for uuid in uuids {
+        let mut config = config.lock().await;
+        let value = match config
+            .hypixel_api
+            .get_skyblock_profiles_by_uuid(&uuid)
+            .await
+        {
+            Ok(sb_response) => sb_response
+                .get_last_played_profile()
+                .and_then(|sb_profile| get_competition_value(sb_profile, stat)),
+            Err(_) => None,
+        };
+        if let Some(value) = value {
+            config
+                .database
+                .get()
+                .await?
+                .query(
+                    "UPDATE competition_entries SET current_value = $3 WHERE competition_id = $1 AND uuid = $2",
+                    &[&id, &uuid, &value],
+                )
+                .await?;
+        }
+    }
+
+    config
+        .lock()
+        .await
+        .database
+        .get()
+        .await?
+        .query(
+            "UPDATE competitions SET updated = $2 WHERE id = $1",
+            &[&id, &get_timestamp_millis()],
+        )
+        .await?;
+
+    Ok(())
+}
+
+async fn end_competition(
+    http: &Arc<HttpClient>,
+    config: &Arc<Mutex<Config>>,
+    id: i32,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let config = config.lock().await;
+    let pool = config.database.get().await?;
+
+    // Another task may have already ended it
+    let competition = match pool
+        .query(
+            "UPDATE competitions SET ended = TRUE WHERE id = $1 AND NOT ended RETURNING *",
+            &[&id],
+        )
+        .await?
+        .into_iter()
+        .next()
+    {
+        Some(competition) => competition,
+        None => return Ok(()),
+    };
+    let mut out = String::from("The competition has ended!\n");
+    out.push_str(&build_competition_standings(&config, &pool, id, 3).await?);
+
+    let channel: String = competition.get("channel");
+    if let Ok(channel) = Id::<ChannelMarker>::from_str(&channel) {
+        http.create_message(channel)
+            .embeds(&[default_embed(&get_competition_title(&competition))
+                .description(out)
+                .build()])?
+            .exec()
+            .await?;
+    }
+
+    Ok(())
+}
+
+fn get_competition_title(competition: &tokio_postgres::Row) -> String {
+    let stat: String = competition.get("stat");
+    let guild_name: Option<String> = competition.get("guild_name");
+    format!(
+        "{} Competition - {}",
+        stat,
+        guild_name.unwrap_or_else(|| "Linked Members".to_string())
+    )
+}
+
+/// Lists the top gains of a competition
+async fn build_competition_standings(
+    config: &Config,
+    pool: &Object,
+    id: i32,
+    limit: i64,
+) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let mut out = String::new();
+    for (idx, db_res) in pool
+        .query(
+            "SELECT uuid, current_value - start_value AS gain FROM competition_entries WHERE competition_id = $1 ORDER BY gain DESC LIMIT $2",
+            &[&id, &limit],
+        )
+        .await?
+        .iter()
+        .enumerate()
+    {
+        let uuid: String = db_res.get("uuid");
+        let gain: f64 = db_res.get("gain");
+        write!(
+            out,
+            "\n{}. [{}](https://mine.ly/{}): +{}",
+            idx + 1,
+            get_username(config, pool, &uuid).await,
+            uuid,
+            format_compact(gain)
+        )?;
+    }
+
+    if out.is_empty() {
+        out.push_str("\nNo participants");
+    }
+    Ok(out)
+}
+
+async fn run_requirement_sweep(http: Arc<HttpClient>, config: Arc<Mutex<Config>>) {
+    // Checked hourly against the last run so restarts don't sweep again
+    let mut interval = tokio::time::interval(Duration::from_secs(60 * 60));
+    loop {
+        interval.tick().await;
+
+        match claim_task_run(&config, "requirement_sweep", 24 * 60 * 60 * 1000).await {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(err) => {
+                println!("Error checking last requirement sweep: {}", err);
+                continue;
+            }
+        }
+        if let Err(err) = sweep_requirements(&http, &config).await {
+            println!("Error sweeping requirements: {}", err);
+        }
+    }
+}
+
+/// Records a run of a task if its last run was at least `period` millis ago
+async fn claim_task_run(
+    config: &Arc<Mutex<Config>>,
+    name: &str,
+    period: i64,
+) -> Result<bool, Box<dyn Error + Send + Sync>> {
+    let now = get_timestamp_millis();
+    Ok(config
+        .lock()
+        .await
+        .database
+        .get()
+        .await?
+        .query_opt(
+            "INSERT INTO task_runs (name, time) VALUES ($1, $2) ON CONFLICT (name) DO UPDATE SET time = EXCLUDED.time WHERE task_runs.time <= $3 RETURNING name",
+            &[&name, &now, &(now - period)],
+        )
+        .await?
+        .is_some())
+}
+
+/// Checks every member of every guild with requirements and reports the changes since the last sweep
+async fn sweep_requirements(
+    http: &Arc<HttpClient>,
+    config: &Arc<Mutex<Config>>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let server_config = {
+        let config = config.lock().await;
+        ServerConfig::read_config(&config.database.get().await?).await
+    };
+    // Warnings and grace periods are tracked even without a channel to report to
+    let sweep_channel: Option<Id<ChannelMarker>> = Id::from_str(&server_config.sweep_channel).ok();
+
+    for (guild_name, guild_reqs) in &server_config.guild_reqs {
+        if let Err(err) = sweep_guild(
+            http,
+            config,
+            &server_config,
+            sweep_channel,
+            guild_name,
+            guild_reqs,
+        )
+        .await
+        {
+            println!("Error sweeping requirements for {}: {}", guild_name, err);
+        }
+    }
+
+    Ok(())
+}
+
+async fn sweep_guild(
+    http: &Arc<HttpClient>,
+    config: &Arc<Mutex<Config>>,
+    server_config: &ServerConfig,
+    sweep_channel: Option<Id<ChannelMarker>>,
+    guild_name: &str,
+    guild_reqs: &GuildReqs,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let guild = match config
+        .lock()
+        .await
+        .hypixel_api
+        .get_guild_by_name(guild_name)
+        .await?
+        .guild
+    {
+        Some(guild) => guild,
+        None => return Ok(()),
+    };
+
+    let previous: HashMap<String, String> = config
+        .lock()
+        .await
+        .database
+        .get()
+        .await?
+        .query(
+            "SELECT uuid, failing FROM req_sweep WHERE guild_id = $1",
+            &[&guild.id],
+        )
+        .await?
+        .iter()
+        .map(|db_res| (db_res.get("uuid"), db_res.get("failing")))
+        .collect();
+
+    let mut newly_failing = Vec::new();
+    let mut still_failing = Vec::new();
+    let mut now_meeting = Vec::new();
+    let mut grace_over = Vec::new();
+    for guild_member in &guild.members {
+        let mut config = config.lock().await;
+        let mut failing = match config
+            .hypixel_api
+            .get_skyblock_profiles_by_uuid(&guild_member.uuid)
+            .await
+        {
+            Ok(sb_response) => match sb_response.get_last_played_profile() {
+                Some(sb_profile) => {
+                    let stats = get_player_stats(sb_profile);
+                    let pool = config.database.get().await?;
+                    let username = get_username(&config, &pool, &guild_member.uuid).await;
+                    cache_player_stats(&pool, &guild_member.uuid, &username, &stats).await?;
+                    guild_reqs.get_failing(&stats)
+                }
+                None => vec!["profile"],
+            },
+            Err(_) => continue,
+        };
+        if guild_member.exp_history.values().sum::<i64>() < guild_reqs.weekly_gexp
+            && !is_new_member(guild_member.joined, get_timestamp_millis())
+        {
+            failing.push("weekly_gexp");
+        }
+        let failing = failing.join(", ");
+
+        let pool = config.database.get().await?;
+        pool.query(
+            "INSERT INTO req_sweep (guild_id, uuid, failing, time) VALUES ($1, $2, $3, $4) ON CONFLICT (guild_id, uuid) DO UPDATE SET failing = EXCLUDED.failing, time = EXCLUDED.time",
+            &[&guild.id, &guild_member.uuid, &failing, &get_timestamp_millis()],
+        )
+        .await?;
+
+        let deadline = update_warning(
+            http,
+            &config,
+            &pool,
+            server_config,
+            &guild,
+            &guild_member.uuid,
+            &failing,
+        )
+        .await?;
+        if deadline.is_some_and(|deadline| deadline <= get_timestamp_millis()) {
+            grace_over.push(get_username(&config, &pool, &guild_member.uuid).await);
+        }
+
+        let was_failing = previous
+            .get(&guild_member.uuid)
+            .is_some_and(|previous| !previous.is_empty());
+        if failing.is_empty() {
+            if was_failing {
+                now_meeting.push(get_username(&config, &pool, &guild_member.uuid).await);
+            }
+        } else {
+            let line = format!(
+                "{} ({})",
+                get_username(&config, &pool, &guild_member.uuid).await,
+                failing
+            );
+            if was_failing {
+                still_failing.push(line);
+            } else {
+                newly_failing.push(line);
+            }
+        }
+    }
+
+    let member_uuids: Vec<&String> = guild
+        .members
+        .iter()
+        .map(|guild_member| &guild_member.uuid)
+        .collect();
+    config
+        .lock()
+        .await
+        .database
+        .get()
+        .await?
+        .query(
+            "DELETE FROM req_sweep WHERE guild_id = $1 AND NOT uuid = ANY($2)",
+            &[&guild.id, &member_uuids],
+        )
+        .await?;
+    config
+        .lock()
+        .await
+        .database
+        .get()
+        .await?
+        .query(
+            "DELETE FROM req_warnings WHERE guild_id = $1 AND NOT uuid = ANY($2)",
+            &[&guild.id, &member_uuids],
+        )
+        .await?;
+
+    let mut eb = default_embed(&format!("Requirement Sweep for {}", guild.name));
+    if previous.is_empty() {
+        eb = eb.field(
+            EmbedFieldBuilder::new(
+                format!("Failing ({})", newly_failing.len()),
+                format_sweep_list(&newly_failing),
+            )
+            .build(),
+        );
+    } else {
+        eb = eb
+            .field(
+                EmbedFieldBuilder::new(
+                    format!("Newly failing ({})", newly_failing.len()),
+                    format_sweep_list(&newly_failing),
+                )
+                .build(),
+            )
+            .field(
+                EmbedFieldBuilder::new(
+                    format!("Now meeting ({})", now_meeting.len()),
+                    format_sweep_list(&now_meeting),
+                )
+                .build(),
+            )
+            .field(
+                EmbedFieldBuilder::new(
+                    format!("Still failing ({})", still_failing.len()),
+                    format_sweep_list(&still_failing),
+                )
+                .build(),
+            );
+    }
+
+    if !grace_over.is_empty() {
+        eb = eb.field(
+            EmbedFieldBuilder::new(
+                format!("Grace period over ({})", grace_over.len()),
+                format_sweep_list(&grace_over),
+            )
+            .build(),
+        );
+    }
+
+    if let Some(sweep_channel) = sweep_channel {
+        http.create_message(sweep_channel)
+            .embeds(&[eb.build()])?
+            .exec()
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Notified players have this long to join before their slot goes to the next in line
+const WAITLIST_CLAIM_MILLIS: i64 = 48 * 60 * 60 * 1000;
+
+/// Drops members who joined or didn't claim their slot from the waitlist and pings the
+/// next in line when slots open up
+async fn process_waitlist(
+    http: &Arc<HttpClient>,
+    config: &Arc<Mutex<Config>>,
+    server_config: &ServerConfig,
+    guild: &Guild,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let config = config.lock().await;
+    let pool = config.database.get().await?;
+    let application_channel =
+        Id::<ChannelMarker>::from_str(&server_config.application_channel).ok();
+
+    let member_uuids: Vec<&String> = guild
+        .members
+        .iter()
+        .map(|guild_member| &guild_member.uuid)
+        .collect();
+    pool.query(
+        "DELETE FROM waitlist WHERE guild_id = $1 AND uuid = ANY($2)",
+        &[&guild.id, &member_uuids],
+    )
+    .await?;
+
+    let expired = pool
+        .query(
+            "DELETE FROM waitlist WHERE guild_id = $1 AND notified < $2 RETURNING uuid, discord",
+            &[&guild.id, &(get_timestamp_millis() - WAITLIST_CLAIM_MILLIS)],
+        )
+        .await?;
+    if let Some(application_channel) = application_channel {
+        for db_res in &expired {
+            let uuid: String = db_res.get("uuid");
+            let discord: String = db_res.get("discord");
+            http.create_message(application_channel)
+                .embeds(&[default_embed("Waitlist")
+                    .description(format!(
+                        "<@{}> ([{}](https://mine.ly/{})) didn't join {} in time and was removed from the waitlist",
+                        discord,
+                        get_username(&config, &pool, &uuid).await,
+                        uuid,
+                        guild.name
+                    ))
+                    .build()])?
+                .exec()
+                .await?;
+        }
+    }
+
+    let open_slots = GUILD_MEMBER_LIMIT.saturating_sub(guild.members.len()) as i64;
+    if open_slots == 0 {
+        return Ok(());
+    }
+
+    // Entries already pinged still hold their slot until they join or it expires
+    for db_res in pool
+        .query(
+            "SELECT * FROM (SELECT * FROM waitlist WHERE guild_id = $1 ORDER BY id LIMIT $2) AS next WHERE notified IS NULL",
+            &[&guild.id, &open_slots],
+        )
+        .await?
+    {
+        let id: i32 = db_res.get("id");
+        let uuid: String = db_res.get("uuid");
+        let discord: String = db_res.get("discord");
+        let username = get_username(&config, &pool, &uuid).await;
+        let notified = get_timestamp_millis();
+
+        pool.query(
+            "UPDATE waitlist SET notified = $2 WHERE id = $1",
+            &[&id, &notified],
+        )
+        .await?;
+
+        if let Ok(user_id) = Id::<UserMarker>::from_str(&discord) {
+            // Staff are still told below if the DM can't be delivered
+            if let Err(err) = send_dm(
+                http,
+                user_id,
+                default_embed("Waitlist")
+                    .description(format!(
+                        "A slot opened up in {}, you are next in line to join! Join <t:{}:R> to keep your spot.",
+                        guild.name,
+                        (notified + WAITLIST_CLAIM_MILLIS) / 1000
+                    ))
+                    .build(),
+            )
+            .await
+            {
+                println!("Error sending waitlist notice to {}: {}", user_id, err);
+            }
+        }
+
+        if let Some(application_channel) = application_channel {
+            http.create_message(application_channel)
+                .embeds(&[default_embed("Waitlist")
+                    .description(format!(
+                        "A slot opened up in {}, next in line is <@{}> ([{}](https://mine.ly/{}))",
+                        guild.name, discord, username, uuid
+                    ))
+                    .build()])?
+                .exec()
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Sends an embed to a user in their DMs
+async fn send_dm(
+    http: &Arc<HttpClient>,
+    user_id: Id<UserMarker>,
+    embed: Embed,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let dm_channel = http
+        .create_private_channel(user_id)
+        .exec()
+        .await?
+        .model()
+        .await?;
+    http.create_message(dm_channel.id)
+        .embeds(&[embed])?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+/// Starts a grace period for a failing member or clears it once they meet requirements again.
+/// Returns the deadline if the member is still within or past a grace period.
+async fn update_warning(
+    http: &Arc<HttpClient>,
+    config: &Config,
+    pool: &Object,
+    server_config: &ServerConfig,
+    guild: &Guild,
+    uuid: &str,
+    failing: &str,
+) -> Result<Option<i64>, Box<dyn Error + Send + Sync>> {
+    if failing.is_empty() {
+        pool.query(
+            "DELETE FROM req_warnings WHERE guild_id = $1 AND uuid = $2",
+            &[&guild.id, &uuid],
+        )
+        .await?;
+        return Ok(None);
+    }
+
+    let db_res_vec = pool
+        .query(
+            "UPDATE req_warnings SET failing = $3 WHERE guild_id = $1 AND uuid = $2 RETURNING deadline",
+            &[&guild.id, &uuid, &failing],
+        )
+        .await?;
+    if let Some(db_res) = db_res_vec.first() {
+        return Ok(Some(db_res.get("deadline")));
+    }
+
+    let warned_at = get_timestamp_millis();
+    let deadline = warned_at + server_config.grace_days * 24 * 60 * 60 * 1000;
+    pool.query(
+        "INSERT INTO req_warnings (guild_id, uuid, failing, warned_at, deadline) VALUES ($1, $2, $3, $4, $5)",
+        &[&guild.id, &uuid, &failing, &warned_at, &deadline],
+    )
+    .await?;
+
+    let username = get_username(config, pool, uuid).await;
+    for db_res in pool
+        .query(
+            "SELECT discord FROM linked_accounts WHERE uuid = $1",
+            &[&uuid],
+        )
+        .await?
+    {
+        let discord: String = db_res.get("discord");
+        if let Ok(user_id) = Id::<UserMarker>::from_str(&discord) {
+            // Members with DMs disabled still show up in the staff list
+            if let Err(err) = send_dm(
+                http,
+                user_id,
+                default_embed("Requirement Warning")
+                    .description(format!(
+                        "{} no longer meets the requirements for {} ({}).\nMeet them again <t:{}:R> to avoid being removed.",
+                        username,
+                        guild.name,
+                        failing,
+                        deadline / 1000
+                    ))
+                    .build(),
+            )
+            .await
+            {
+                println!("Error sending requirement warning to {}: {}", user_id, err);
+            }
+        }
+    }
+
+    Ok(Some(deadline))
+}
+
+/// Joins lines while staying under the embed field limit
+fn format_sweep_list(lines: &[String]) -> String {
+    if lines.is_empty() {
+        return "None".to_string();
+    }
+
+    let mut out = String::new();
+    for (idx, line) in lines.iter().enumerate() {
+        if out.len() + line.len() > 1000 {
+            let _ = write!(out, "\n{} more", lines.len() - idx);
+            break;
+        }
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+const USERNAME_LOOKUP_DELAY: Duration = Duration::from_secs(1);
+
+async fn sync_usernames(
+    http: &Arc<HttpClient>,
+    config: &Arc<Mutex<Config>>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let accounts: Vec<_> = {
+        let config = config.lock().await;
+        let pool = config.database.get().await?;
+        pool.query("SELECT * FROM linked_accounts", &[])
+            .await?
+            .iter()
+            .map(LinkedAccount::from_row)
+            .collect()
+    };
+
+    let server_config = {
+        let config = config.lock().await;
+        ServerConfig::read_config(&config.database.get().await?).await
+    };
+    for account in accounts {
+        {
+            let config = config.lock().await;
+            if let Ok(uuid_res) = config.hypixel_api.uuid_to_username(&account.uuid).await {
+                let pool = config.database.get().await?;
+                if let Err(err) = refresh_username(
+                    http,
+                    &config,
+                    &pool,
+                    &server_config,
+                    &account.uuid,
+                    &uuid_res.username,
+                )
+                .await
+                {
+                    println!("Error refreshing username of {}: {}", account.uuid, err);
+                }
+            }
+        }
+
+        // Spreads the lookups out to stay under the Mojang rate limit
+        tokio::time::sleep(USERNAME_LOOKUP_DELAY).await;
+    }
+
+    Ok(())
+}
+
+async fn sync_guilds(
+    http: &Arc<HttpClient>,
+    config: &Arc<Mutex<Config>>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let (guild_id, server_config, accounts) = {
+        let config = config.lock().await;
+        let pool = config.database.get().await?;
+        let accounts: Vec<_> = pool
+            .query("SELECT * FROM linked_accounts", &[])
+            .await?
+            .iter()
+            .map(LinkedAccount::from_row)
+            .collect();
+        (
+            config.guild_id,
+            ServerConfig::read_config(&pool).await,
+            accounts,
+        )
+    };
+    let members = get_all_members(http, guild_id).await?;
+    let linked: HashSet<&str> = accounts
+        .iter()
+        .map(|account| account.discord.as_str())
+        .collect();
+
+    let (guilds, failed_guild_ids) = get_configured_guilds(config, &server_config).await;
+    // Discord user to the roles wanted across every guild, so guilds sharing a role
+    // don't remove what another guild added
+    let mut wanted_roles: HashMap<&str, HashSet<Id<RoleMarker>>> = HashMap::new();
+    let mut managed_roles = HashSet::new();
+    for guild in &guilds {
+        if let Err(err) =
+            announce_guild_changes(http, config, &server_config, guild, &accounts).await
+        {
+            println!("Error announcing changes for {}: {}", guild.name, err);
+        }
+        if let Err(err) = record_guild_exp(&config.lock().await.database.get().await?, guild).await
+        {
+            println!("Error recording guild exp for {}: {}", guild.name, err);
+        }
+        if let Err(err) = process_waitlist(http, config, &server_config, guild).await {
+            println!("Error processing waitlist for {}: {}", guild.name, err);
+        }
+
+        let guild_role = get_guild_role(&server_config, &guild.id);
+        let rank_roles = get_rank_roles(&server_config, &guild.id);
+        managed_roles.extend(guild_role);
+        managed_roles.extend(rank_roles.values().copied());
+
+        let guild_ranks: HashMap<&str, String> = guild
+            .members
+            .iter()
+            .map(|guild_member| {
+                (
+                    guild_member.uuid.as_str(),
+                    normalize_rank(&guild_member.rank),
+                )
+            })
+            .collect();
+        for account in &accounts {
+            if let Some(rank) = guild_ranks.get(account.uuid.as_str()) {
+                let member_roles = wanted_roles.entry(account.discord.as_str()).or_default();
+                member_roles.extend(guild_role);
+                member_roles.extend(rank_roles.get(rank).copied());
+            }
+        }
+    }
+    // Membership in guilds that couldn't be fetched is unknown, so keep their roles
+    let mut unknown_roles = HashSet::new();
+    for hypixel_guild_id in &failed_guild_ids {
+        unknown_roles.extend(get_guild_role(&server_config, hypixel_guild_id));
+        unknown_roles.extend(get_rank_roles(&server_config, hypixel_guild_id).into_values());
+    }
+
+    let mut role_changes = Vec::new();
+    for member in &members {
+        let user_id = member.user.id.to_string();
+        if !linked.contains(user_id.as_str()) {
+            continue;
+        }
+        let member_wanted = wanted_roles.get(user_id.as_str());
+
+        for role in &managed_roles {
+            let wanted = member_wanted.is_some_and(|member_wanted| member_wanted.contains(role));
+            let has_role = member.roles.contains(role);
+            if wanted && !has_role {
+                match http
+                    .add_guild_member_role(guild_id, member.user.id, *role)
+                    .exec()
+                    .await
+                {
+                    Ok(_) => role_changes.push(format!("<@{}> +<@&{}>", user_id, role)),
+                    Err(err) => println!("Error adding role {} to {}: {}", role, user_id, err),
+                }
+            } else if !wanted && has_role && !unknown_roles.contains(role) {
+                match http
+                    .remove_guild_member_role(guild_id, member.user.id, *role)
+                    .exec()
+                    .await
+                {
+                    Ok(_) => role_changes.push(format!("<@{}> -<@&{}>", user_id, role)),
+                    Err(err) => println!("Error removing role {} from {}: {}", role, user_id, err),
+                }
+            }
+        }
+    }
+
+    if !role_changes.is_empty() {
+        send_log(
+            http,
+            &server_config,
+            default_embed("Guild Sync")
+                .description(format!(
+                    "Synced guild roles\n\n{}",
+                    format_sweep_list(&role_changes)
+                ))
+                .build(),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Ids of the guilds with roles configured and the requirement guilds seen by the guild sync
+async fn get_configured_guild_ids(
+    pool: &Object,
+    server_config: &ServerConfig,
+) -> Result<Vec<String>, tokio_postgres::Error> {
+    let mut guild_ids: HashSet<String> = server_config
+        .guild_roles
+        .keys()
+        .chain(server_config.rank_roles.keys())
+        .cloned()
+        .collect();
+    for db_res in pool
+        .query("SELECT DISTINCT guild_id FROM guild_members", &[])
+        .await?
+    {
+        guild_ids.insert(db_res.get("guild_id"));
+    }
+    Ok(guild_ids.into_iter().collect())
+}
+
+/// Whether a guild has roles or requirements configured
+fn is_configured_guild(server_config: &ServerConfig, guild: &Guild) -> bool {
+    server_config.guild_roles.contains_key(&guild.id)
+        || server_config.rank_roles.contains_key(&guild.id)
+        || server_config
+            .guild_reqs
+            .contains_key(&guild.name.to_lowercase())
+}
+
+fn get_guild_role(server_config: &ServerConfig, hypixel_guild_id: &str) -> Option<Id<RoleMarker>> {
+    server_config
+        .guild_roles
+        .get(hypixel_guild_id)
+        .and_then(|guild_role| Id::from_str(guild_role).ok())
+}
+
+/// Lowercase rank name to role for a Hypixel guild
+fn get_rank_roles(
+    server_config: &ServerConfig,
+    hypixel_guild_id: &str,
+) -> HashMap<String, Id<RoleMarker>> {
+    server_config
+        .rank_roles
+        .get(hypixel_guild_id)
+        .map(|guild_rank_roles| {
+            guild_rank_roles
+                .iter()
+                .filter_map(|(rank, rank_role)| {
+                    Id::from_str(rank_role)
+                        .ok()
+                        .map(|rank_role| (rank.clone(), rank_role))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Guilds with roles or requirements configured and the ids of guilds that couldn't be fetched
+async fn get_configured_guilds(
+    config: &Arc<Mutex<Config>>,
+    server_config: &ServerConfig,
+) -> (Vec<Guild>, HashSet<String>) {
+    let mut guilds: Vec<Guild> = Vec::new();
+    let mut failed_guild_ids = HashSet::new();
+
+    let hypixel_guild_ids: HashSet<&String> = server_config
+        .guild_roles
+        .keys()
+        .chain(server_config.rank_roles.keys())
+        .collect();
+    for hypixel_guild_id in hypixel_guild_ids {
+        let guild_res = config
+            .lock()
+            .await
+            .hypixel_api
+            .get_guild_by_id(hypixel_guild_id)
+            .await;
+        match guild_res {
+            Ok(guild_res) => guilds.extend(guild_res.guild),
+            Err(err) => {
+                println!("Error fetching guild {}: {}", hypixel_guild_id, err);
+                failed_guild_ids.insert(hypixel_guild_id.to_string());
+            }
+        }
+    }
+
+    for guild_name in server_config.guild_reqs.keys() {
+        if guilds
+            .iter()
+            .any(|guild| &guild.name.to_lowercase() == guild_name)
+        {
+            continue;
+        }
+
+        let guild_res = config
+            .lock()
+            .await
+            .hypixel_api
+            .get_guild_by_name(guild_name)
+            .await;
+        match guild_res {
+            Ok(guild_res) => guilds.extend(guild_res.guild),
+            Err(err) => println!("Error fetching guild {}: {}", guild_name, err),
+        }
+    }
+
+    (guilds, failed_guild_ids)
+}
+
+/// Refreshes cached stats older than six hours for linked accounts and synced guild members
+async fn refresh_stats_cache(
+    config: &Arc<Mutex<Config>>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let uuids: Vec<String> = {
+        let config = config.lock().await;
+        let pool = config.database.get().await?;
+        pool.query(
+            "SELECT uuid FROM (SELECT uuid FROM linked_accounts UNION SELECT uuid FROM guild_members) AS tracked WHERE uuid NOT IN (SELECT uuid FROM player_stats WHERE updated > $1)",
+            &[&(get_timestamp_millis() - 6 * 60 * 60 * 1000)],
+        )
+        .await?
+        .iter()
+        .map(|db_res| db_res.get("uuid"))
+        .collect()
+    };
+
+    for uuid in uuids {
+        let mut config = config.lock().await;
+        let sb_response = match config
+            .hypixel_api
+            .get_skyblock_profiles_by_uuid(&uuid)
+            .await
+        {
+            Ok(sb_response) => sb_response,
+            Err(_) => continue,
+        };
+        if let Some(sb_profile) = sb_response.get_last_played_profile() {
+            let stats = get_player_stats(sb_profile);
+            let pool = config.database.get().await?;
+            let username = get_username(&config, &pool, &uuid).await;
+            cache_player_stats(&pool, &uuid, &username, &stats).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn cache_player_stats(
+    pool: &Object,
+    uuid: &str,
+    username: &str,
+    stats: &PlayerStats,
+) -> Result<(), tokio_postgres::Error> {
+    pool.query(
+        "INSERT INTO player_stats (uuid, username, slayer, skills, catacombs, weight, level, updated) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) ON CONFLICT (uuid) DO UPDATE SET username = EXCLUDED.username, slayer = EXCLUDED.slayer, skills = EXCLUDED.skills, catacombs = EXCLUDED.catacombs, weight = EXCLUDED.weight, level = EXCLUDED.level, updated = EXCLUDED.updated",
+        &[
+            &uuid,
+            &username,
+            &stats.slayer,
+            &stats.skills,
+            &stats.catacombs,
+            &stats.weight,
+            &stats.level,
+            &get_timestamp_millis(),
+        ],
+    )
+    .await?;
+
+    // Keep roughly one snapshot a day for progress reports
+    pool.query(
+        "INSERT INTO stat_snapshots (uuid, time, slayer, skills, catacombs, weight, level) SELECT $1, $2, $3, $4, $5, $6, $7 WHERE NOT EXISTS (SELECT 1 FROM stat_snapshots WHERE uuid = $1 AND time > $2 - 20 * 60 * 60 * 1000)",
+        &[
+            &uuid,
+            &get_timestamp_millis(),
+            &stats.slayer,
+            &stats.skills,
+            &stats.catacombs,
+            &stats.weight,
+            &stats.level,
+        ],
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Stores the daily exp history of a guild, only configured guilds are tracked
+async fn record_guild_exp(pool: &Object, guild: &Guild) -> Result<(), tokio_postgres::Error> {
+    if !is_configured_guild(&ServerConfig::read_config(pool).await, guild) {
+        return Ok(());
+    }
+
+    for guild_member in &guild.members {
+        for (date, exp) in &guild_member.exp_history {
+            pool.query(
+                "INSERT INTO guild_exp (guild_id, uuid, date, exp) VALUES ($1, $2, $3, $4) ON CONFLICT (guild_id, uuid, date) DO UPDATE SET exp = EXCLUDED.exp",
+                &[&guild.id, &guild_member.uuid, date, exp],
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Diffs a guild's members against the last snapshot and posts joins, leaves and rank changes
+async fn announce_guild_changes(
+    http: &Arc<HttpClient>,
+    config: &Arc<Mutex<Config>>,
+    server_config: &ServerConfig,
+    guild: &Guild,
+    accounts: &[LinkedAccount],
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let config = config.lock().await;
+    let mut pool = config.database.get().await?;
+
+    let previous_ranks: HashMap<String, String> = pool
+        .query(
+            "SELECT uuid, rank FROM guild_members WHERE guild_id = $1",
+            &[&guild.id],
+        )
+        .await?
+        .iter()
+        .map(|row| (row.get("uuid"), row.get("rank")))
+        .collect();
+
+    // Replace the snapshot atomically so a failure can't leave it partially written
+    let transaction = pool.transaction().await?;
+    transaction
+        .query(
+            "DELETE FROM guild_members WHERE guild_id = $1",
+            &[&guild.id],
+        )
+        .await?;
+    for guild_member in &guild.members {
+        transaction
+            .query(
+                "INSERT INTO guild_members (guild_id, uuid, rank, joined) VALUES ($1, $2, $3, $4)",
+                &[
+                    &guild.id,
+                    &guild_member.uuid,
+                    &guild_member.rank,
+                    &guild_member.joined,
+                ],
+            )
+            .await?;
+    }
+    transaction.commit().await?;
+
+    // Nothing to compare against the first time a guild is seen
+    if previous_ranks.is_empty() {
+        return Ok(());
+    }
+
+    let mut rank_priorities: HashMap<String, i64> = guild
+        .ranks
+        .iter()
+        .map(|rank| (normalize_rank(&rank.name), rank.priority))
+        .collect();
+    rank_priorities.insert("guild master".to_string(), i64::MAX);
+
+    let mut changes = Vec::new();
+    for guild_member in &guild.members {
+        match previous_ranks.get(&guild_member.uuid) {
+            None => changes.push((&guild_member.uuid, "Joined".to_string())),
+            Some(previous_rank) if previous_rank != &guild_member.rank => {
+                let old_priority = rank_priorities.get(&normalize_rank(previous_rank));
+                let new_priority = rank_priorities.get(&normalize_rank(&guild_member.rank));
+                changes.push((
+                    &guild_member.uuid,
+                    format!(
+                        "{} from {} to {}",
+                        if new_priority > old_priority {
+                            "Promoted"
+                        } else {
+                            "Demoted"
+                        },
+                        previous_rank,
+                        guild_member.rank
+                    ),
+                ));
+            }
+            _ => {}
+        }
+    }
+    let current_uuids: HashSet<&str> = guild
+        .members
+        .iter()
+        .map(|guild_member| guild_member.uuid.as_str())
+        .collect();
+    for uuid in previous_ranks.keys() {
+        if !current_uuids.contains(uuid.as_str()) {
+            changes.push((uuid, "Left or was kicked".to_string()));
+        }
+    }
+
+    if changes.is_empty() {
+        return Ok(());
+    }
+    let guild_log_channel = match Id::<ChannelMarker>::from_str(&server_config.guild_log_channel) {
+        Ok(guild_log_channel) => guild_log_channel,
+        Err(_) => return Ok(()),
+    };
+
+    let mut out = String::new();
+    for (uuid, change) in changes {
+        let username = match config.hypixel_api.uuid_to_username(uuid).await {
+            Ok(uuid_res) => uuid_res.username,
+            Err(_) => uuid.to_string(),
+        };
+        write!(out, "\n[{}](https://mine.ly/{})", username, uuid)?;
+        if let Some(account) = accounts.iter().find(|account| &account.uuid == uuid) {
+            write!(out, " (<@{}>)", account.discord)?;
+        }
+        write!(out, ": {}", change)?;
+    }
+
+    http.create_message(guild_log_channel)
+        .embeds(&[default_embed(&format!("{} Updates", guild.name))
+            .description(out.trim())
+            .build()])?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+/// Updates the stored username of a linked account if the player renamed and returns the old username
+async fn refresh_username(
+    http: &Arc<HttpClient>,
+    config: &Config,
+    pool: &Object,
+    server_config: &ServerConfig,
+    uuid: &str,
+    username: &str,
+) -> Result<Option<String>, Box<dyn Error + Send + Sync>> {
+    if username.is_empty() {
+        return Ok(None);
+    }
+
+    let db_res_vec = pool
+        .query(
+            "SELECT username FROM linked_accounts WHERE uuid = $1",
+            &[&uuid],
+        )
+        .await?;
+    let old_username: String = match db_res_vec.first() {
+        Some(db_res) => db_res.get("username"),
+        None => return Ok(None),
+    };
+    if old_username == username {
+        return Ok(None);
+    }
+
+    release_username(config, pool, username, uuid).await?;
+    pool.query(
+        "UPDATE linked_accounts SET username = $1 WHERE uuid = $2",
+        &[&username, &uuid],
+    )
+    .await?;
+    pool.query(
+        "INSERT INTO name_history (uuid, old_username, new_username, time) VALUES ($1, $2, $3, $4)",
+        &[&uuid, &old_username, &username, &get_timestamp_millis()],
+    )
+    .await?;
+
+    if server_config.announce_renames {
+        send_log(
+            http,
+            server_config,
+            default_embed("Username Changed")
+                .description(format!(
+                    "[{}](https://mine.ly/{}) was previously known as {}",
+                    username, uuid, old_username
+                ))
+                .build(),
+        )
+        .await?;
+    }
+
+    Ok(Some(old_username))
+}
+
+/// Whoever held this name before has renamed too, so refresh their row to keep the UNIQUE constraint free
+async fn release_username(
+    config: &Config,
+    pool: &Object,
+    username: &str,
+    uuid: &str,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    for db_res in pool
+        .query(
+            "SELECT uuid FROM linked_accounts WHERE LOWER(username) = LOWER($1) AND uuid != $2",
+            &[&username, &uuid],
+        )
+        .await?
+    {
+        let other_uuid: String = db_res.get("uuid");
+        // The stored name is kept if the lookup fails rather than replacing it with the uuid
+        let other_username = match config.hypixel_api.uuid_to_username(&other_uuid).await {
+            Ok(uuid_res) if !uuid_res.username.is_empty() => uuid_res.username,
+            _ => {
+                return Err(format!(
+                    "{} is still linked to another account, try again later",
+                    username
+                )
+                .into())
+            }
+        };
+        pool.query(
+            "UPDATE linked_accounts SET username = $1 WHERE uuid = $2",
+            &[&other_username, &other_uuid],
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn handle_member_add(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    member: Box<MemberAdd>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut config = config.lock().await;
+    let pool = config.database.get().await?;
+    let accounts = LinkedAccount::get_by_discord(&pool, &member.user.id.to_string()).await?;
+
+    let server_config = ServerConfig::read_config(&pool).await;
+    if !accounts.is_empty() {
+        apply_link_roles(
+            http,
+            &mut config,
+            &server_config,
+            member.guild_id,
+            member.user.id,
+            &accounts,
+            server_config.rejoin_reqs,
+        )
+        .await?;
+    } else if let Ok(unverified_role) = Id::from_str(&server_config.unverified_role) {
+        http.add_guild_member_role(member.guild_id, member.user.id, unverified_role)
+            .exec()
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn handle_member_remove(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    member: MemberRemove,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let config = config.lock().await;
+    let pool = config.database.get().await?;
+    let user_id = member.user.id.to_string();
+    let accounts = LinkedAccount::get_by_discord(&pool, &user_id).await?;
+    if accounts.is_empty() {
+        return Ok(());
+    }
+
+    let server_config = ServerConfig::read_config(&pool).await;
+    if server_config.leave_policy != LeavePolicy::Keep {
+        let time = get_timestamp_millis();
+        if server_config.leave_policy == LeavePolicy::Archive {
+            pool.query(
+                "INSERT INTO archived_accounts (uuid, username, discord, last_updated, archived_at) SELECT uuid, username, discord, last_updated, $2 FROM linked_accounts WHERE discord = $1",
+                &[&user_id, &time],
+            )
+            .await?;
+        }
+        pool.query(
+            "DELETE FROM linked_accounts WHERE discord = $1",
+            &[&user_id],
+        )
+        .await?;
+        for account in &accounts {
+            account.record_history(&pool, "unlink", time).await?;
+        }
+    }
+
+    let mut out = format!(
+        "<@{}> ({}#{}) left while linked to:",
+        user_id,
+        member.user.name,
+        member.user.discriminator()
+    );
+    for account in &accounts {
+        write!(
+            out,
+            "\n  • [{}](https://mine.ly/{})",
+            account.username, account.uuid
+        )?;
+    }
+    write!(out, "\nLink policy: {}", server_config.leave_policy.name())?;
+
+    send_log(
+        http,
+        &server_config,
+        default_embed("Member Left").description(out).build(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Alerts staff when a Minecraft account has been linked to several Discord users or the other way around
+async fn check_alt_accounts(
+    http: &Arc<HttpClient>,
+    pool: &Object,
+    server_config: &ServerConfig,
+    account: &LinkedAccount,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let other_discords = pool
+        .query(
+            "SELECT DISTINCT discord FROM link_history WHERE uuid = $1 AND discord != $2 AND action = 'link'",
+            &[&account.uuid, &account.discord],
+        )
+        .await?;
+    let other_uuids = pool
+        .query(
+            "SELECT DISTINCT ON (uuid) username, uuid FROM link_history WHERE discord = $1 AND uuid != $2 AND action = 'link' AND uuid NOT IN (SELECT uuid FROM linked_accounts WHERE discord = $1)",
+            &[&account.discord, &account.uuid],
+        )
+        .await?;
+
+    if other_discords.is_empty() && other_uuids.is_empty() {
+        return Ok(());
+    }
+
+    let mut out = format!(
+        "<@{}> linked to [{}](https://mine.ly/{})",
+        account.discord, account.username, account.uuid
+    );
+    if !other_discords.is_empty() {
+        write!(out, "\n\n{} was previously linked to:", account.username)?;
+        for row in other_discords {
+            let discord: String = row.get("discord");
+            write!(out, "\n  • <@{}>", discord)?;
+        }
+    }
+    if !other_uuids.is_empty() {
+        write!(out, "\n\n<@{}> was previously linked to:", account.discord)?;
+        for row in other_uuids {
+            let username: String = row.get("username");
+            let uuid: String = row.get("uuid");
+            write!(out, "\n  • [{}](https://mine.ly/{})", username, uuid)?;
+        }
+    }
+
+    send_log(
+        http,
+        server_config,
+        default_embed("Possible Alt Account")
+            .description(out)
+            .build(),
+    )
+    .await
+}
+
+async fn send_log(
+    http: &Arc<HttpClient>,
+    server_config: &ServerConfig,
+    embed: Embed,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    if let Ok(log_channel) = Id::from_str(&server_config.log_channel) {
+        http.create_message(log_channel)
+            .embeds(&[embed])?
+            .exec()
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn get_all_members(
+    http: &Arc<HttpClient>,
+    guild_id: Id<GuildMarker>,
+) -> Result<Vec<Member>, Box<dyn Error + Send + Sync>> {
+    let mut members: Vec<Member> = Vec::new();
+    loop {
+        let mut request = http.guild_members(guild_id).limit(1000)?;
+        if let Some(last_member) = members.last() {
+            request = request.after(last_member.user.id);
+        }
+
+        let page = request.exec().await?.model().await?;
+        let page_len = page.len();
+        members.extend(page);
+        if page_len < 1000 {
+            break;
+        }
+    }
+
+    Ok(members)
+}
+
+async fn role_exists(
+    http: &Arc<HttpClient>,
+    guild_id: Id<GuildMarker>,
+    role_id: Id<RoleMarker>,
+) -> Result<bool, Box<dyn Error + Send + Sync>> {
+    Ok(http
+        .roles(guild_id)
+        .exec()
+        .await?
+        .model()
+        .await?
+        .iter()
+        .any(|role| role.id == role_id))
+}
+
+async fn channel_exists(
+    http: &Arc<HttpClient>,
+    guild_id: Id<GuildMarker>,
+    channel_id: Id<ChannelMarker>,
+) -> Result<bool, Box<dyn Error + Send + Sync>> {
+    Ok(http
+        .guild_channels(guild_id)
+        .exec()
+        .await?
+        .model()
+        .await?
+        .iter()
+        .any(|channel| channel.id == channel_id))
+}
+
+/// Gives a linked member the verified role, the roles of every Hypixel guild one of their accounts is in
+/// and optionally their primary IGN as nickname
+async fn apply_link_roles(
+    http: &Arc<HttpClient>,
+    config: &mut MutexGuard<'_, Config>,
+    server_config: &ServerConfig,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    accounts: &[LinkedAccount],
+    check_reqs: bool,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    http.add_guild_member_role(
+        guild_id,
+        user_id,
+        Id::from_str(&server_config.verified_role)?,
+    )
+    .exec()
+    .await?;
+    if let Ok(unverified_role) = Id::from_str(&server_config.unverified_role) {
+        http.remove_guild_member_role(guild_id, user_id, unverified_role)
+            .exec()
+            .await?;
+    }
+
+    if server_config.sync_nickname {
+        if let Some(primary) = accounts.iter().find(|account| account.is_primary) {
+            // Discord rejects nicknames for the owner and members above the bot, roles still apply
+            if let Err(err) = http
+                .update_guild_member(guild_id, user_id)
+                .nick(Some(&primary.username))?
+                .exec()
+                .await
+            {
+                println!("Error syncing nickname of {}: {}", user_id, err);
+            }
+        }
+    }
+
+    let member_roles = http
+        .guild_member(guild_id, user_id)
+        .exec()
+        .await?
+        .model()
+        .await?
+        .roles;
+    // Roles wanted across every account, a role can be shared between guilds
+    let mut wanted_roles = HashSet::new();
+    // Rank roles are only removed when every lookup gave a definitive answer
+    let mut complete = true;
+    for account in accounts {
+        let player_guild = match config.hypixel_api.get_guild_by_player(&account.uuid).await {
+            Ok(guild_res) => match guild_res.guild {
+                Some(player_guild) => player_guild,
+                None => continue,
+            },
+            Err(_) => {
+                complete = false;
+                continue;
+            }
+        };
+        let guild_member_role = get_guild_role(server_config, &player_guild.id);
+        let guild_rank_roles = get_rank_roles(server_config, &player_guild.id);
+        if guild_member_role.is_none() && guild_rank_roles.is_empty() {
+            continue;
+        }
+
+        if check_reqs {
+            if let Some(reqs) = server_config
+                .guild_reqs
+                .get(&player_guild.name.to_lowercase())
+            {
+                let sb_response = match config
+                    .hypixel_api
+                    .get_skyblock_profiles_by_uuid(&account.uuid)
+                    .await
+                {
+                    Ok(sb_response) => sb_response,
+                    Err(_) => {
+                        complete = false;
+                        continue;
+                    }
+                };
+                let meets_reqs = sb_response
+                    .get_last_played_profile()
+                    .map(|profile| reqs.is_met(&get_player_stats(profile)))
+                    .unwrap_or(false);
+                if !meets_reqs {
+                    continue;
+                }
+            }
+        }
+
+        if let Some(guild_member_role) = guild_member_role {
+            wanted_roles.insert(guild_member_role);
+            http.add_guild_member_role(guild_id, user_id, guild_member_role)
+                .exec()
+                .await?;
+        }
+
+        if let Some(rank_role) = player_guild
+            .members
+            .iter()
+            .find(|guild_member| guild_member.uuid == account.uuid)
+            .and_then(|guild_member| guild_rank_roles.get(&normalize_rank(&guild_member.rank)))
+        {
+            wanted_roles.insert(*rank_role);
+            if !member_roles.contains(rank_role) {
+                http.add_guild_member_role(guild_id, user_id, *rank_role)
+                    .exec()
+                    .await?;
+            }
+        }
+    }
+
+    // Swap out rank roles from a previous rank
+    if complete {
+        for hypixel_guild_id in server_config.rank_roles.keys() {
+            for rank_role in get_rank_roles(server_config, hypixel_guild_id).values() {
+                if member_roles.contains(rank_role) && !wanted_roles.contains(rank_role) {
+                    http.remove_guild_member_role(guild_id, user_id, *rank_role)
+                        .exec()
+                        .await?;
+                }
+            }
+        }
+    }
+
+    if !server_config.stat_roles.is_empty() {
+        let account_stats = get_account_stats(config, accounts).await;
+        apply_stat_roles(
+            http,
+            server_config,
+            guild_id,
+            user_id,
+            &account_stats,
+            &member_roles,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Current stats of the last played profile of every account whose profiles could be fetched
+async fn get_account_stats(
+    config: &mut MutexGuard<'_, Config>,
+    accounts: &[LinkedAccount],
+) -> Vec<PlayerStats> {
+    let mut account_stats = Vec::new();
+    for account in accounts {
+        let sb_response = match config
+            .hypixel_api
+            .get_skyblock_profiles_by_uuid(&account.uuid)
+            .await
+        {
+            Ok(sb_response) => sb_response,
+            Err(_) => continue,
+        };
+        if let Some(sb_profile) = sb_response.get_last_played_profile() {
+            account_stats.push(get_player_stats(sb_profile));
+        }
+    }
+
+    account_stats
+}
+
+/// Syncs the stat threshold roles using the best stats across a user's accounts.
+/// Returns the stat roles the user qualifies for.
+async fn apply_stat_roles(
+    http: &Arc<HttpClient>,
+    server_config: &ServerConfig,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    account_stats: &[PlayerStats],
+    member_roles: &[Id<RoleMarker>],
+) -> Result<Vec<Id<RoleMarker>>, Box<dyn Error + Send + Sync>> {
+    let mut earned_roles = Vec::new();
+    if server_config.stat_roles.is_empty() {
+        return Ok(earned_roles);
+    }
+
+    let mut best_stats: HashMap<&str, f64> = HashMap::new();
+    for stats in account_stats {
+        for name in PlayerStats::NAMES {
+            let value = stats.get(name).unwrap_or(0.0);
+            let best = best_stats.entry(name).or_insert(value);
+            *best = best.max(value);
+        }
+    }
+    // Don't strip roles because the API was unavailable
+    if best_stats.is_empty() {
+        return Ok(earned_roles);
+    }
+
+    for (stat_role_id, stat_role) in &server_config.stat_roles {
+        let stat_role_id = Id::from_str(stat_role_id)?;
+        let meets_threshold = best_stats
+            .get(stat_role.stat.as_str())
+            .is_some_and(|value| *value >= stat_role.threshold);
+
+        if meets_threshold {
+            earned_roles.push(stat_role_id);
+            if !member_roles.contains(&stat_role_id) {
+                http.add_guild_member_role(guild_id, user_id, stat_role_id)
+                    .exec()
+                    .await?;
+            }
+        } else if member_roles.contains(&stat_role_id) {
+            http.remove_guild_member_role(guild_id, user_id, stat_role_id)
+                .exec()
+                .await?;
+        }
+    }
+
+    Ok(earned_roles)
+}
+
+async fn handle_unknown_command(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &Box<InteractionCreate>,
+    interaction_data: &Box<CommandData>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_followup(&interaction.token)
+        .embeds(&[default_embed("Error")
+            .description("Unknown Command")
+            .build()])?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+async fn handle_reqs_command(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &Box<InteractionCreate>,
+    interaction_data: &Box<CommandData>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut player = String::new();
+    for opt in &interaction_data.options {
+        if opt.name == "player" {
+            if let CommandOptionValue::String(opt_str) = &opt.value {
+                player = opt_str.to_string();
+            }
+        }
+    }
+
+    let mut profile = String::new();
+    for opt in &interaction_data.options {
+        if opt.name == "profile" {
+            if let CommandOptionValue::String(opt_str) = &opt.value {
+                profile = opt_str.to_string();
+            }
+        }
+    }
+
+    let mut config = config.lock().await;
+
+    let uuid_response = config.hypixel_api.username_to_uuid(&player).await?;
+    let sb_response = config
+        .hypixel_api
+        .get_skyblock_profiles_by_uuid(&uuid_response.uuid)
+        .await?;
+    let sb_profile = if profile.is_empty() {
+        sb_response.get_last_played_profile()
+    } else {
+        sb_response.get_profile_by_name(&profile)
+    }
+    .ok_or("No profile found")?;
+
+    let stats = get_player_stats(sb_profile);
+
+    let mut eb = default_embed("Requirement Checker").description(format!(
+        "Slayer: {}\nSkills: {:.2}\nCatacombs: {:.2}\nWeight: {:.2}",
+        stats.slayer, stats.skills, stats.catacombs, stats.weight
+    ));
+
+    let pool = config.database.get().await?;
+    let server_config = ServerConfig::read_config(&pool).await;
+
+    for req in server_config.guild_reqs {
+        let cur_reqs = req.1;
+        eb = eb.field(
+            EmbedFieldBuilder::new(
+                req.0,
+                format!(
+                    "{}\nslayer = {}, skills = {}, cata = {}, weight = {}",
+                    if cur_reqs.is_met(&stats) {
+                        "Meets requirements"
+                    } else {
+                        "Does not meet requirements"
+                    },
+                    cur_reqs.slayer,
+                    cur_reqs.skills,
+                    cur_reqs.catacombs,
+                    cur_reqs.weight
+                ),
+            )
+            .build(),
+        );
+    }
+
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_followup(&interaction.token)
+        .embeds(&[eb.build()])?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+async fn handle_verify_command(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &Box<InteractionCreate>,
+    interaction_data: &Box<CommandData>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut player = String::new();
+    let mut alt = false;
+    let mut primary = false;
+    for opt in &interaction_data.options {
+        if opt.name == "player" {
+            if let CommandOptionValue::String(opt_str) = &opt.value {
+                player = opt_str.to_string();
+            }
+        } else if opt.name == "alt" {
+            if let CommandOptionValue::Boolean(opt_bool) = &opt.value {
+                alt = *opt_bool;
+            }
+        } else if opt.name == "primary" {
+            if let CommandOptionValue::Boolean(opt_bool) = &opt.value {
+                primary = *opt_bool;
+            }
+        }
+    }
+
+    let eb = verify_player(http, config, interaction, player, alt, primary).await?;
+
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_followup(&interaction.token)
+        .embeds(&[eb.build()])?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+async fn handle_verify_panel_button(
+    http: &Arc<HttpClient>,
+    interaction: &InteractionCreate,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_response(
+            interaction.id,
+            &interaction.token,
+            &InteractionResponse {
+                kind: InteractionResponseType::Modal,
+                data: Some(
+                    InteractionResponseDataBuilder::new()
+                        .custom_id("verify_modal")
+                        .title("Verify")
+                        .components([Component::ActionRow(ActionRow {
+                            components: vec![Component::TextInput(TextInput {
+                                custom_id: "player".to_string(),
+                                label: "Your in-game username".to_string(),
+                                max_length: Some(16),
+                                min_length: Some(1),
+                                placeholder: None,
+                                required: Some(true),
+                                style: TextInputStyle::Short,
+                                value: None,
+                            })],
+                        })])
+                        .build(),
+                ),
+            },
+        )
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+async fn handle_verify_modal(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &InteractionCreate,
+    modal_data: &ModalInteractionData,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut player = String::new();
+    for row in &modal_data.components {
+        for component in &row.components {
+            if component.custom_id == "player" {
+                player = component
+                    .value
+                    .clone()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string();
+            }
+        }
+    }
+
+    let eb = verify_player(http, config, interaction, player, false, false).await?;
+
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_followup(&interaction.token)
+        .embeds(&[eb.build()])?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+async fn handle_primary_command(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &InteractionCreate,
+    interaction_data: &CommandData,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut player = String::new();
+    for opt in &interaction_data.options {
+        if opt.name == "player" {
+            if let CommandOptionValue::String(opt_str) = &opt.value {
+                player = opt_str.to_string();
+            }
+        }
+    }
+
+    let mut config = config.lock().await;
+    let mut pool = config.database.get().await?;
+    let user_id = interaction
+        .member
+        .as_ref()
+        .unwrap()
+        .user
+        .as_ref()
+        .unwrap()
+        .id;
+
+    let uuid_res = config.hypixel_api.username_to_uuid(&player).await?;
+    let accounts = LinkedAccount::get_by_discord(&pool, &user_id.to_string()).await?;
+    let account = accounts
+        .iter()
+        .find(|account| account.uuid == uuid_res.uuid)
+        .ok_or(format!("{} is not linked to you", uuid_res.username))?;
+
+    let eb = if account.is_primary {
+        default_embed("Primary Account").description(format!(
+            "{} is already your primary account",
+            uuid_res.username
+        ))
+    } else {
+        let transaction = pool.transaction().await?;
+        transaction
+            .query(
+                "UPDATE linked_accounts SET is_primary = FALSE WHERE discord = $1",
+                &[&account.discord],
+            )
+            .await?;
+        transaction
+            .query(
+                "UPDATE linked_accounts SET is_primary = TRUE WHERE uuid = $1",
+                &[&account.uuid],
+            )
+            .await?;
+        transaction.commit().await?;
+
+        // Refreshes the nickname for the new primary
+        let server_config = ServerConfig::read_config(&pool).await;
+        apply_link_roles(
+            http,
+            &mut config,
+            &server_config,
+            interaction.guild_id.unwrap(),
+            user_id,
+            &LinkedAccount::get_by_discord(&pool, &account.discord).await?,
+            false,
+        )
+        .await?;
+
+        default_embed("Primary Account")
+            .description(format!("{} is now your primary account", uuid_res.username))
+    };
+
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_followup(&interaction.token)
+        .embeds(&[eb.build()])?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+/// Links the interaction's user to a player if the Discord tag set on Hypixel matches
+async fn verify_player(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &InteractionCreate,
+    player: String,
+    alt: bool,
+    primary: bool,
+) -> Result<EmbedBuilder, Box<dyn Error + Send + Sync>> {
+    let eb;
+
+    let mut config = config.lock().await;
+
+    let discord_info = get_discord_info(&mut config, player).await;
+    if let Some(err) = discord_info.error {
+        eb = default_embed("Verify").description(err);
+    } else {
+        let user = interaction.member.as_ref().unwrap().user.as_ref().unwrap();
+        let user_tag = format!("{}#{}", user.name, user.discriminator());
+        let api_discord_tag = discord_info.discord.unwrap();
+
+        if api_discord_tag != user_tag {
+            eb = default_embed("Verify").description(format!(
+                "Your Discord tag (`{}`) does not match the in-game Discord tag (`{}`)",
+                user_tag, api_discord_tag
+            ));
+        } else {
+            let user_id = user.id.to_string();
+            let username = discord_info.username.unwrap();
+            let uuid = discord_info.uuid.unwrap();
+
+            let pool = config.database.get().await?;
+            let time = get_timestamp_millis();
+            release_username(&config, &pool, &username, &uuid).await?;
+            // Alts keep the user's other accounts and `primary` demotes the current primary,
+            // a normal link replaces the primary
+            let removed_rows = if alt || primary {
+                pool.query(
+                    "DELETE FROM linked_accounts WHERE username = $1 or uuid = $2 RETURNING *",
+                    &[&username, &uuid],
+                )
+                .await
+            } else {
+                pool.query(
+                    "DELETE FROM linked_accounts WHERE (discord = $1 AND is_primary) OR username = $2 or uuid = $3 RETURNING *",
+                    &[&user_id, &username, &uuid],
+                )
+                .await
+            };
+            if let Ok(removed_rows) = removed_rows {
+                for removed_row in removed_rows {
+                    LinkedAccount::from_row(&removed_row)
+                        .record_history(&pool, "unlink", time)
+                        .await?;
+                }
+            }
+
+            let has_primary = !pool
+                .query(
+                    "SELECT uuid FROM linked_accounts WHERE discord = $1 AND is_primary",
+                    &[&user_id],
+                )
+                .await?
+                .is_empty();
+            let is_primary = primary || !has_primary;
+            if is_primary && has_primary {
+                pool.query(
+                    "UPDATE linked_accounts SET is_primary = FALSE WHERE discord = $1",
+                    &[&user_id],
+                )
+                .await?;
+            }
+            let db_res = pool.query("INSERT INTO linked_accounts (last_updated, discord, username, uuid, is_primary) VALUES ($1, $2, $3, $4, $5)", &[&time, &user_id, &username, &uuid, &is_primary] ).await;
+
+            if db_res.is_ok() {
+                let server_config = ServerConfig::read_config(&pool).await;
+                let account = LinkedAccount {
+                    uuid,
+                    username,
+                    discord: user_id,
+                    is_primary,
+                };
+                account.record_history(&pool, "link", time).await?;
+                // The link already exists, so a failed alert shouldn't stop roles being applied
+                if let Err(err) = check_alt_accounts(http, &pool, &server_config, &account).await {
+                    println!("Error checking alt accounts of {}: {}", account.uuid, err);
+                }
+
+                apply_link_roles(
+                    http,
+                    &mut config,
+                    &server_config,
+                    interaction.guild_id.unwrap(),
+                    user.id,
+                    &LinkedAccount::get_by_discord(&pool, &account.discord).await?,
+                    false,
+                )
+                .await?;
+
+                eb = default_embed("Verify").description(format!(
+                    "Successfully linked {} to {}{}",
+                    user_tag,
+                    account.username,
+                    if account.is_primary {
+                        " as your primary account"
+                    } else {
+                        " as an alt account"
+                    }
+                ));
+            } else {
+                eb = default_embed("Verify").description("Error inserting into database");
+            }
+        }
+    }
+
+    Ok(eb)
+}
+
+async fn handle_settings_command(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &Box<InteractionCreate>,
+    interaction_data: &Box<CommandData>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let eb;
+
+    let mut command = String::new();
+    let mut raw_command = String::new();
+    for opt in &interaction_data.options {
+        if opt.name == "command" {
+            if let CommandOptionValue::String(opt_str) = &opt.value {
+                command = opt_str.to_lowercase();
+                raw_command = opt_str.to_string();
+            }
+        }
+    }
+
+    let mut config = config.lock().await;
+    let pool = config.database.get().await?;
+    let mut server_config = ServerConfig::read_config(&pool).await;
+
+    let cmd_args: Vec<_> = command.split(' ').collect();
+    if cmd_args.len() == 2 && cmd_args.first().unwrap() == &"verified_role" {
+        let verified_role =
+            Id::from_str(&cmd_args.get(1).unwrap().replace("<@&", "").replace('>', ""))?;
+        let mut found_role = false;
+
+        let roles = http
+            .roles(interaction.guild_id.unwrap())
+            .exec()
+            .await?
+            .model()
+            .await?;
+        for role in roles {
+            if role.id == verified_role {
+                found_role = true;
+                break;
+            }
+        }
+
+        if found_role {
+            server_config.verified_role = verified_role.to_string();
+            server_config.write_config(&pool).await;
+            eb = default_embed("Settings")
+                .description(format!("Set verified role to <@&{}>", verified_role));
+        } else {
+            eb = default_embed("Settings")
+                .description(format!("Invalid role: <@&{}>", verified_role));
+        }
+    } else if cmd_args.len() == 2 && cmd_args.first().unwrap() == &"unverified_role" {
+        let unverified_role =
+            Id::from_str(&cmd_args.get(1).unwrap().replace("<@&", "").replace('>', ""))?;
+
+        if role_exists(http, interaction.guild_id.unwrap(), unverified_role).await? {
+            server_config.unverified_role = unverified_role.to_string();
+            server_config.write_config(&pool).await;
+            eb = default_embed("Settings")
+                .description(format!("Set unverified role to <@&{}>", unverified_role));
+        } else {
+            eb = default_embed("Settings")
+                .description(format!("Invalid role: <@&{}>", unverified_role));
+        }
+    } else if cmd_args.len() == 2 && cmd_args.first().unwrap() == &"verify_panel" {
+        let panel_channel: Id<ChannelMarker> =
+            Id::from_str(&cmd_args.get(1).unwrap().replace("<#", "").replace('>', ""))?;
+
+        http.create_message(panel_channel)
+            .embeds(&[default_embed("Verify")
+                .description("Click the button below and enter your in-game username to link your Hypixel account")
+                .build()])?
+            .components(&[Component::ActionRow(ActionRow {
+                components: vec![Component::Button(Button {
+                    custom_id: Some("verify_panel".to_string()),
+                    disabled: false,
+                    emoji: None,
+                    label: Some("Verify".to_string()),
+                    style: ButtonStyle::Primary,
+                    url: None,
+                })],
+            })])?
+            .exec()
+            .await?;
+        eb = default_embed("Settings")
+            .description(format!("Sent verification panel to <#{}>", panel_channel));
+    } else if cmd_args.len() >= 3 && cmd_args.first().unwrap() == &"guild_role" {
+        let guild_role_raw = cmd_args.last().unwrap();
+        let guild_role = Id::from_str(&guild_role_raw.replace("<@&", "").replace('>', ""))?;
+        let guild_name = command
+            .split("guild_role ")
+            .last()
+            .unwrap()
+            .split(guild_role_raw)
+            .next()
+            .unwrap()
+            .trim();
+        let mut found_role = false;
+
+        for role in http
+            .roles(interaction.guild_id.unwrap())
+            .exec()
+            .await?
+            .model()
+            .await?
+        {
+            if role.id == guild_role {
+                found_role = true;
+            }
+        }
+
+        if !found_role {
+            eb = default_embed("Settings").description(format!("Invalid role: <@&{}>", guild_role));
+        } else {
+            let guild = config
+                .hypixel_api
+                .get_guild_by_name(guild_name)
+                .await?
+                .guild
+                .ok_or("Invalid guild")?;
+            server_config
+                .guild_roles
+                .insert(guild.id, guild_role.to_string());
+            server_config.write_config(&pool).await;
+            eb = default_embed("Settings").description(format!(
+                "Set guild role for {} to <@&{}>",
+                guild.name, guild_role
+            ));
+        }
+    } else if cmd_args.len() >= 3 && cmd_args.first().unwrap() == &"rank_role" {
+        let rank_args = command.split_once("rank_role ").unwrap().1;
+        if let Some(guild_name) = rank_args.strip_prefix("clear ") {
+            let guild = config
+                .hypixel_api
+                .get_guild_by_name(guild_name.trim())
+                .await?
+                .guild
+                .ok_or("Invalid guild")?;
+            server_config.rank_roles.remove(&guild.id);
+            server_config.write_config(&pool).await;
+            eb = default_embed("Settings")
+                .description(format!("Cleared rank roles for {}", guild.name));
+        } else {
+            let rank_role_raw = cmd_args.last().unwrap();
+            let rank_role = Id::from_str(&rank_role_raw.replace("<@&", "").replace('>', ""))?;
+            let (guild_name, rank_name) = rank_args
+                .split(rank_role_raw)
+                .next()
+                .unwrap()
+                .split_once(',')
+                .ok_or("Separate the guild and rank with a comma")?;
+
+            if !role_exists(http, interaction.guild_id.unwrap(), rank_role).await? {
+                eb = default_embed("Settings")
+                    .description(format!("Invalid role: <@&{}>", rank_role));
+            } else {
+                let guild = config
+                    .hypixel_api
+                    .get_guild_by_name(guild_name.trim())
+                    .await?
+                    .guild
+                    .ok_or("Invalid guild")?;
+                let rank = normalize_rank(rank_name);
+
+                if rank != "guild master"
+                    && !guild
+                        .ranks
+                        .iter()
+                        .any(|guild_rank| normalize_rank(&guild_rank.name) == rank)
+                {
+                    eb = default_embed("Settings").description(format!(
+                        "Invalid rank for {}: {}",
+                        guild.name,
+                        rank_name.trim()
+                    ));
+                } else {
+                    server_config
+                        .rank_roles
+                        .entry(guild.id)
+                        .or_default()
+                        .insert(rank.clone(), rank_role.to_string());
+                    server_config.write_config(&pool).await;
+                    eb = default_embed("Settings").description(format!(
+                        "Set {} rank role for {} to <@&{}>",
+                        rank, guild.name, rank_role
+                    ));
+                }
+            }
+        }
+    } else if cmd_args.len() == 2 && cmd_args.first().unwrap() == &"sync_nickname" {
+        server_config.sync_nickname = cmd_args.get(1).unwrap().parse()?;
+        server_config.write_config(&pool).await;
+        eb = default_embed("Settings").description(format!(
+            "Set nickname syncing to {}",
+            server_config.sync_nickname
+        ));
+    } else if cmd_args.len() == 2 && cmd_args.first().unwrap() == &"rejoin_reqs" {
+        server_config.rejoin_reqs = cmd_args.get(1).unwrap().parse()?;
+        server_config.write_config(&pool).await;
+        eb = default_embed("Settings").description(format!(
+            "Set requirement check on rejoin to {}",
+            server_config.rejoin_reqs
+        ));
+    } else if cmd_args.len() == 2 && cmd_args.first().unwrap() == &"announce_renames" {
+        server_config.announce_renames = cmd_args.get(1).unwrap().parse()?;
+        server_config.write_config(&pool).await;
+        eb = default_embed("Settings").description(format!(
+            "Set rename announcements to {}",
+            server_config.announce_renames
+        ));
+    } else if cmd_args.len() == 2 && cmd_args.first().unwrap() == &"leave_policy" {
+        if let Some(leave_policy) = LeavePolicy::from_name(cmd_args.get(1).unwrap()) {
+            server_config.leave_policy = leave_policy;
+            server_config.write_config(&pool).await;
+            eb = default_embed("Settings")
+                .description(format!("Set leave policy to {}", leave_policy.name()));
+        } else {
+            eb = default_embed("Settings").description("Invalid leave policy");
+        }
+    } else if cmd_args.len() == 2 && cmd_args.first().unwrap() == &"inactivity_days" {
+        let inactivity_days: i64 = cmd_args.get(1).unwrap().parse()?;
+        if inactivity_days < 1 {
+            return Err("Inactivity days must be at least 1".into());
+        }
+        server_config.inactivity_days = inactivity_days;
+        server_config.write_config(&pool).await;
+        eb = default_embed("Settings").description(format!(
+            "Set inactivity threshold to {} days",
+            server_config.inactivity_days
+        ));
+    } else if cmd_args.len() >= 3 && cmd_args.first().unwrap() == &"kick_exempt" {
+        let uuid_res = config
+            .hypixel_api
+            .username_to_uuid(cmd_args.get(2).unwrap())
+            .await?;
+
+        if cmd_args.get(1).unwrap() == &"add" {
+            // Reasons keep the casing they were written with
+            let reason = raw_command.split(' ').skip(3).collect::<Vec<_>>().join(" ");
+            server_config
+                .kick_exempt
+                .insert(uuid_res.uuid, reason.clone());
+            server_config.write_config(&pool).await;
+            eb = default_embed("Settings").description(if reason.is_empty() {
+                format!("Exempted {} from the kick list", uuid_res.username)
+            } else {
+                format!(
+                    "Exempted {} from the kick list: {}",
+                    uuid_res.username, reason
+                )
+            });
+        } else if cmd_args.get(1).unwrap() == &"remove" {
+            if server_config.kick_exempt.remove(&uuid_res.uuid).is_some() {
+                server_config.write_config(&pool).await;
+                eb = default_embed("Settings").description(format!(
+                    "Removed kick list exemption for {}",
+                    uuid_res.username
+                ));
+            } else {
+                eb = default_embed("Settings")
+                    .description(format!("{} is not exempt", uuid_res.username));
+            }
+        } else {
+            eb = default_embed("Settings").description("Invalid command");
+        }
+    } else if cmd_args.len() == 2 && cmd_args.first().unwrap() == &"grace_days" {
+        let grace_days: i64 = cmd_args.get(1).unwrap().parse()?;
+        if grace_days < 1 {
+            return Err("Grace days must be at least 1".into());
+        }
+        server_config.grace_days = grace_days;
+        server_config.write_config(&pool).await;
+        eb = default_embed("Settings").description(format!(
+            "Set requirement grace period to {} days",
+            server_config.grace_days
+        ));
+    } else if cmd_args.len() == 2 && cmd_args.first().unwrap() == &"application_channel" {
+        let application_channel: Id<ChannelMarker> =
+            Id::from_str(&cmd_args.get(1).unwrap().replace("<#", "").replace('>', ""))?;
+
+        if channel_exists(http, interaction.guild_id.unwrap(), application_channel).await? {
+            server_config.application_channel = application_channel.to_string();
+            server_config.write_config(&pool).await;
+            eb = default_embed("Settings").description(format!(
+                "Set application channel to <#{}>",
+                application_channel
+            ));
+        } else {
+            eb = default_embed("Settings")
+                .description(format!("Invalid channel: <#{}>", application_channel));
+        }
+    } else if cmd_args.len() == 3
+        && cmd_args.first().unwrap() == &"stat_role"
+        && cmd_args.get(1).unwrap() == &"remove"
+    {
+        let stat_role: Id<RoleMarker> =
+            Id::from_str(&cmd_args.get(2).unwrap().replace("<@&", "").replace('>', ""))?;
+
+        if server_config
+            .stat_roles
+            .remove(&stat_role.to_string())
+            .is_some()
+        {
+            server_config.write_config(&pool).await;
+            eb = default_embed("Settings")
+                .description(format!("Removed stat role <@&{}>", stat_role));
+        } else {
+            eb = default_embed("Settings")
+                .description(format!("<@&{}> is not a stat role", stat_role));
+        }
+    } else if cmd_args.len() == 4 && cmd_args.first().unwrap() == &"stat_role" {
+        let stat = cmd_args.get(1).unwrap();
+        let stat_role: Id<RoleMarker> =
+            Id::from_str(&cmd_args.get(3).unwrap().replace("<@&", "").replace('>', ""))?;
+
+        if !PlayerStats::NAMES.contains(stat) {
+            eb = default_embed("Settings").description(format!(
+                "Invalid stat, must be one of: {}",
+                PlayerStats::NAMES.join(", ")
+            ));
+        } else if let Some(threshold) = parse_amount(cmd_args.get(2).unwrap()) {
+            if role_exists(http, interaction.guild_id.unwrap(), stat_role).await? {
+                server_config.stat_roles.insert(
+                    stat_role.to_string(),
+                    StatRole {
+                        stat: stat.to_string(),
+                        threshold,
+                    },
+                );
+                server_config.write_config(&pool).await;
+                eb = default_embed("Settings").description(format!(
+                    "Set <@&{}> for {} {} or higher",
+                    stat_role, stat, threshold
+                ));
+            } else {
+                eb = default_embed("Settings")
+                    .description(format!("Invalid role: <@&{}>", stat_role));
+            }
+        } else {
+            eb = default_embed("Settings").description("Invalid threshold");
+        }
+    } else if cmd_args.len() == 2 && cmd_args.first().unwrap() == &"sweep_channel" {
+        let sweep_channel: Id<ChannelMarker> =
+            Id::from_str(&cmd_args.get(1).unwrap().replace("<#", "").replace('>', ""))?;
+
+        if channel_exists(http, interaction.guild_id.unwrap(), sweep_channel).await? {
+            server_config.sweep_channel = sweep_channel.to_string();
+            server_config.write_config(&pool).await;
+            eb = default_embed("Settings").description(format!(
+                "Set requirement sweep channel to <#{}>",
+                sweep_channel
+            ));
+        } else {
+            eb = default_embed("Settings")
+                .description(format!("Invalid channel: <#{}>", sweep_channel));
+        }
+    } else if cmd_args.len() == 2 && cmd_args.first().unwrap() == &"guild_log_channel" {
+        let guild_log_channel: Id<ChannelMarker> =
+            Id::from_str(&cmd_args.get(1).unwrap().replace("<#", "").replace('>', ""))?;
+
+        if channel_exists(http, interaction.guild_id.unwrap(), guild_log_channel).await? {
+            server_config.guild_log_channel = guild_log_channel.to_string();
+            server_config.write_config(&pool).await;
+            eb = default_embed("Settings")
+                .description(format!("Set guild log channel to <#{}>", guild_log_channel));
+        } else {
+            eb = default_embed("Settings")
+                .description(format!("Invalid channel: <#{}>", guild_log_channel));
+        }
+    } else if cmd_args.len() == 2 && cmd_args.first().unwrap() == &"log_channel" {
+        let log_channel: Id<ChannelMarker> =
+            Id::from_str(&cmd_args.get(1).unwrap().replace("<#", "").replace('>', ""))?;
+        let mut found_channel = false;
+
+        for channel in http
+            .guild_channels(interaction.guild_id.unwrap())
+            .exec()
+            .await?
+            .model()
+            .await?
+        {
+            if channel.id == log_channel {
+                found_channel = true;
+            }
+        }
+
+        if found_channel {
+            server_config.log_channel = log_channel.to_string();
+            server_config.write_config(&pool).await;
+            eb = default_embed("Settings")
+                .description(format!("Set log channel to <#{}>", log_channel));
+        } else {
+            eb = default_embed("Settings")
+                .description(format!("Invalid channel: <#{}>", log_channel));
+        }
+    } else if cmd_args.len() == 1 && cmd_args.get(0).unwrap() == &"view" {
+        let mut out = format!(
+            "Verified Role: <@&{}>\nUnverified Role: <@&{}>\nLog Channel: <#{}>\nGuild Log Channel: <#{}>\nSweep Channel: <#{}>\nApplication Channel: <#{}>\nGrace Days: {}\nSync Nickname: {}\nRejoin Reqs: {}\nLeave Policy: {}\nAnnounce Renames: {}\nInactivity Days: {}\nGuild Roles:",
+            server_config.verified_role,
+            server_config.unverified_role,
+            server_config.log_channel,
+            server_config.guild_log_channel,
+            server_config.sweep_channel,
+            server_config.application_channel,
+            server_config.grace_days,
+            server_config.sync_nickname,
+            server_config.rejoin_reqs,
+            server_config.leave_policy.name(),
+            server_config.announce_renames,
+            server_config.inactivity_days
+        );
+        for guild_role in server_config.guild_roles {
+            let guild_res = config.hypixel_api.get_guild_by_id(&guild_role.0).await?;
+            write!(
+                out,
+                "\n  • {}: <@&{}>",
+                guild_res.guild.ok_or("Invalid guild")?.name,
+                guild_role.1
+            )?;
+        }
+        write!(out, "\nRank Roles:")?;
+        for rank_roles in server_config.rank_roles {
+            let guild_res = config.hypixel_api.get_guild_by_id(&rank_roles.0).await?;
+            write!(
+                out,
+                "\n  • {}:",
+                guild_res.guild.ok_or("Invalid guild")?.name
+            )?;
+            for rank_role in rank_roles.1 {
+                write!(out, "\n    • {}: <@&{}>", rank_role.0, rank_role.1)?;
+            }
+        }
+        write!(out, "\nStat Roles:")?;
+        for (stat_role, threshold) in &server_config.stat_roles {
+            write!(
+                out,
+                "\n  • <@&{}>: {} >= {}",
+                stat_role, threshold.stat, threshold.threshold
+            )?;
+        }
+        write!(out, "\nKick Exempt:")?;
+        for (uuid, reason) in &server_config.kick_exempt {
+            write!(
+                out,
+                "\n  • [{}](https://mine.ly/{}){}",
+                get_username(&config, &pool, uuid).await,
+                uuid,
+                if reason.is_empty() {
+                    String::new()
+                } else {
+                    format!(": {}", reason)
+                }
+            )?;
+        }
+        write!(out, "\nReqs:")?;
+        for guild_req in server_config.guild_reqs {
+            write!(
+                out,
+                "\n  • {}: slayer = {}, skills = {}, cata = {}, weight = {}, weekly gexp = {}",
+                guild_req.0,
+                guild_req.1.slayer,
+                guild_req.1.skills,
+                guild_req.1.catacombs,
+                guild_req.1.weight,
+                guild_req.1.weekly_gexp
+            )?;
+        }
+        eb = default_embed("Settings").description(out);
+    } else if cmd_args.len() >= 3 && cmd_args.get(0).unwrap() == &"reqs" {
+        let guild_name = cmd_args.get(2).unwrap();
+        if cmd_args.get(1).unwrap() == &"clear" {
+            server_config.guild_reqs.remove(&guild_name.to_string());
+            server_config.write_config(&pool).await;
+            eb = default_embed("Settings").description(format!("Cleared reqs for {}", guild_name));
+        } else if cmd_args.get(1).unwrap() == &"set" && cmd_args.len() == 5 {
+            let mut cur_reqs = server_config
+                .guild_reqs
+                .remove(&guild_name.to_string())
+                .unwrap_or_default();
+
+            let req_name = cmd_args.get(3).unwrap();
+            let req_amt: i64 = cmd_args.get(4).unwrap().parse()?;
+
+            let mut valid_req = true;
+            match cmd_args.get(3).unwrap() {
+                &"slayer" => cur_reqs.slayer = req_amt,
+                &"skills" => cur_reqs.skills = req_amt,
+                &"catacombs" => cur_reqs.catacombs = req_amt,
+                &"weight" => cur_reqs.weight = req_amt,
+                &"weekly_gexp" => cur_reqs.weekly_gexp = req_amt,
+                _ => valid_req = false,
+            };
+
+            if valid_req {
+                server_config
+                    .guild_reqs
+                    .insert(guild_name.to_string(), cur_reqs);
+
+                server_config.write_config(&pool).await;
+                eb = default_embed("Settings").description(format!(
+                    "Set {} req to {} for {}",
+                    req_name, req_amt, guild_name
+                ));
+            } else {
+                eb = default_embed("Settings").description("Invalid requirement type")
+            }
+        } else {
+            eb = default_embed("Settings").description("Invalid command");
+        }
+    } else {
+        eb = default_embed("Settings").description("Invalid command");
+    }
+
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_followup(&interaction.token)
+        .embeds(&[eb.build()])?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+async fn handle_user_command(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &Box<InteractionCreate>,
+    interaction_data: &Box<CommandData>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut user = &Id::from_str("1")?;
+    for opt in &interaction_data.options {
+        if opt.name == "user" {
+            if let CommandOptionValue::User(opt_user) = &opt.value {
+                user = opt_user;
+            }
+        }
+    }
+
+    let config = config.lock().await;
+    let pool = config.database.get().await?;
+    let server_config = ServerConfig::read_config(&pool).await;
+    let accounts = LinkedAccount::get_by_discord(&pool, &user.to_string()).await?;
+    if accounts.is_empty() {
+        return Err("User is not linked".into());
+    }
+
+    let mut out = format!("<@{}> is linked to:", user);
+    for account in accounts {
+        let mut username = account.username;
+        if let Ok(uuid_res) = config.hypixel_api.uuid_to_username(&account.uuid).await {
+            if refresh_username(
+                http,
+                &config,
+                &pool,
+                &server_config,
+                &account.uuid,
+                &uuid_res.username,
+            )
+            .await?
+            .is_some()
+            {
+                username = uuid_res.username;
+            }
+        }
+
+        write!(
+            out,
+            "\n  • [{}](https://mine.ly/{}){}",
+            username,
+            account.uuid,
+            if account.is_primary { " (primary)" } else { "" }
+        )?;
+    }
+
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_followup(&interaction.token)
+        .embeds(&[default_embed("User Information").description(out).build()])?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+async fn handle_apply_command(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &InteractionCreate,
+    interaction_data: &CommandData,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut guild_name = String::new();
+    for opt in &interaction_data.options {
+        if opt.name == "guild" {
+            if let CommandOptionValue::String(opt_str) = &opt.value {
+                guild_name = opt_str.to_string();
+            }
+        }
+    }
+    let user_id = interaction
+        .member
+        .as_ref()
+        .unwrap()
+        .user
+        .as_ref()
+        .unwrap()
+        .id;
+
+    let mut config = config.lock().await;
+    let pool = config.database.get().await?;
+    let server_config = ServerConfig::read_config(&pool).await;
+
+    let application_channel: Id<ChannelMarker> = Id::from_str(&server_config.application_channel)
+        .map_err(|_| "Applications are not set up")?;
+    let account = LinkedAccount::get_by_discord(&pool, &user_id.to_string())
+        .await?
+        .into_iter()
+        .next()
+        .ok_or("You must link your account with /verify before applying")?;
+
+    let guild = config
+        .hypixel_api
+        .get_guild_by_name(&guild_name)
+        .await?
+        .guild
+        .ok_or("Invalid guild")?;
+    if guild
+        .members
+        .iter()
+        .any(|guild_member| guild_member.uuid == account.uuid)
+    {
+        return Err(format!("You are already in {}", guild.name).into());
+    }
+    if !pool
+        .query(
+            "SELECT id FROM applications WHERE guild_id = $1 AND discord = $2 AND status = 'pending'",
+            &[&guild.id, &user_id.to_string()],
+        )
+        .await?
+        .is_empty()
+    {
+        return Err(format!("You already have a pending application to {}", guild.name).into());
+    }
+
+    let sb_response = config
+        .hypixel_api
+        .get_skyblock_profiles_by_uuid(&account.uuid)
+        .await?;
+    let stats = get_player_stats(
+        sb_response
+            .get_last_played_profile()
+            .ok_or("No profile found")?,
+    );
+    let failing = server_config
+        .guild_reqs
+        .get(&guild.name.to_lowercase())
+        .map(|guild_reqs| guild_reqs.get_failing(&stats))
+        .unwrap_or_default();
+
+    let thread = http
+        .create_thread(
+            application_channel,
+            &format!("{} - {}", guild.name, account.username),
+            ChannelType::GuildPrivateThread,
+        )?
+        .invitable(false)
+        .exec()
+        .await?
+        .model()
+        .await?;
+
+    let application_id: i32 = pool
+        .query_one(
+            "INSERT INTO applications (guild_id, guild_name, uuid, discord, thread, status, created) VALUES ($1, $2, $3, $4, $5, 'pending', $6) RETURNING id",
+            &[
+                &guild.id,
+                &guild.name,
+                &account.uuid,
+                &user_id.to_string(),
+                &thread.id.to_string(),
+                &get_timestamp_millis(),
+            ],
+        )
+        .await?
+        .get("id");
+
+    http.create_message(thread.id)
+        .embeds(&[default_embed("Guild Application")
+            .description(format!(
+                "<@{}> applied to {} as [{}](https://mine.ly/{})\nSlayer: {}\nSkills: {:.2}\nCatacombs: {:.2}\nWeight: {:.2}\n{}",
+                user_id,
+                guild.name,
+                account.username,
+                account.uuid,
+                stats.slayer,
+                stats.skills,
+                stats.catacombs,
+                stats.weight,
+                if failing.is_empty() {
+                    "Meets requirements".to_string()
+                } else {
+                    format!("Does not meet requirements: {}", failing.join(", "))
+                }
+            ))
+            .build()])?
+        .components(&[Component::ActionRow(ActionRow {
+            components: vec![
+                Component::Button(Button {
+                    custom_id: Some(format!("application_accept:{}", application_id)),
+                    disabled: false,
+                    emoji: None,
+                    label: Some("Accept".to_string()),
+                    style: ButtonStyle::Success,
+                    url: None,
+                }),
+                Component::Button(Button {
+                    custom_id: Some(format!("application_deny:{}", application_id)),
+                    disabled: false,
+                    emoji: None,
+                    label: Some("Deny".to_string()),
+                    style: ButtonStyle::Danger,
+                    url: None,
+                }),
+            ],
+        })])?
+        .exec()
+        .await?;
+
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_followup(&interaction.token)
+        .embeds(&[default_embed("Guild Application")
+            .description(format!(
+                "Your application to {} has been sent to staff",
+                guild.name
+            ))
+            .build()])?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+/// Whether the member who used the interaction can manage the server
+fn is_staff(interaction: &InteractionCreate) -> bool {
+    interaction
+        .member
+        .as_ref()
+        .and_then(|member| member.permissions)
+        .is_some_and(|permissions| {
+            permissions.intersects(Permissions::MANAGE_GUILD | Permissions::ADMINISTRATOR)
+        })
+}
+
+async fn respond_ephemeral(
+    http: &Arc<HttpClient>,
+    interaction: &InteractionCreate,
+    content: &str,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_response(
+            interaction.id,
+            &interaction.token,
+            &InteractionResponse {
+                kind: InteractionResponseType::ChannelMessageWithSource,
+                data: Some(
+                    InteractionResponseDataBuilder::new()
+                        .content(content)
+                        .flags(MessageFlags::EPHEMERAL)
+                        .build(),
+                ),
+            },
+        )
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+async fn handle_application_button(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &InteractionCreate,
+    component_data: &MessageComponentInteractionData,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let (action, application_id) = component_data
+        .custom_id
+        .split_once(':')
+        .ok_or("Invalid application button")?;
+    let application_id: i32 = application_id.parse()?;
+    let accepted = action == "application_accept";
+    let reviewer = interaction
+        .member
+        .as_ref()
+        .unwrap()
+        .user
+        .as_ref()
+        .unwrap()
+        .id;
+    if !is_staff(interaction) {
+        respond_ephemeral(http, interaction, "Only staff can review applications").await?;
+        return Ok(());
+    }
+
+    let mut config = config.lock().await;
+    let pool = config.database.get().await?;
+    let server_config = ServerConfig::read_config(&pool).await;
+
+    let db_res = match pool
+        .query_opt(
+            "SELECT * FROM applications WHERE id = $1 AND status = 'pending'",
+            &[&application_id],
+        )
+        .await?
+    {
+        Some(db_res) => db_res,
+        None => {
+            respond_ephemeral(http, interaction, "This application was already reviewed").await?;
+            return Ok(());
+        }
+    };
+    let guild_id: String = db_res.get("guild_id");
+    let guild_name: String = db_res.get("guild_name");
+    let uuid: String = db_res.get("uuid");
+    let discord: String = db_res.get("discord");
+    let applicant: Id<UserMarker> = Id::from_str(&discord)?;
+
+    // The decision is only stored once the role and waitlist are updated so a failure can be retried
+    let mut waitlist_position = None;
+    if accepted {
+        // Players already waiting keep their place in line over new applicants
+        let waiting: i64 = pool
+            .query_one(
+                "SELECT COUNT(*) FROM waitlist WHERE guild_id = $1",
+                &[&guild_id],
+            )
+            .await?
+            .get(0);
+        let guild = config.hypixel_api.get_guild_by_id(&guild_id).await?.guild;
+
+        // Applicants not in the guild yet get the role from the hourly sync once they join
+        if let (Some(guild), Some(guild_role)) = (&guild, server_config.guild_roles.get(&guild_id))
+        {
+            if guild
+                .members
+                .iter()
+                .any(|guild_member| guild_member.uuid == uuid)
+            {
+                http.add_guild_member_role(
+                    interaction.guild_id.unwrap(),
+                    applicant,
+                    Id::from_str(guild_role)?,
+                )
+                .exec()
+                .await?;
+            }
+        }
+
+        if guild.is_some_and(|guild| guild.members.len() + waiting as usize >= GUILD_MEMBER_LIMIT) {
+            pool.query(
+                "INSERT INTO waitlist (guild_id, uuid, discord, added) VALUES ($1, $2, $3, $4) ON CONFLICT (guild_id, uuid) DO NOTHING",
+                &[&guild_id, &uuid, &discord, &get_timestamp_millis()],
+            )
+            .await?;
+            let position: i64 = pool
+                .query_one(
+                    "SELECT COUNT(*) FROM waitlist WHERE guild_id = $1 AND id <= (SELECT id FROM waitlist WHERE guild_id = $1 AND uuid = $2)",
+                    &[&guild_id, &uuid],
+                )
+                .await?
+                .get(0);
+            waitlist_position = Some(position);
+        }
+    }
+    if pool
+        .query_opt(
+            "UPDATE applications SET status = $2, reviewer = $3, decided = $4 WHERE id = $1 AND status = 'pending' RETURNING id",
+            &[
+                &application_id,
+                &if accepted { "accepted" } else { "denied" },
+                &reviewer.to_string(),
+                &get_timestamp_millis(),
+            ],
+        )
+        .await?
+        .is_none()
+    {
+        respond_ephemeral(http, interaction, "This application was already reviewed").await?;
+        return Ok(());
+    }
+
+    let mut decision = format!(
+        "<@{}> {} the application of <@{}> ([{}](https://mine.ly/{})) to {}",
+        reviewer,
+        if accepted { "accepted" } else { "denied" },
+        applicant,
+        get_username(&config, &pool, &uuid).await,
+        uuid,
+        guild_name
+    );
+    if let Some(position) = waitlist_position {
+        write!(
+            decision,
+            "\n{} has no free slots, added to the waitlist at position {}",
+            guild_name, position
+        )?;
+    }
+    send_log(
+        http,
+        &server_config,
+        default_embed(if accepted {
+            "Application Accepted"
+        } else {
+            "Application Denied"
+        })
+        .description(&decision)
+        .build(),
+    )
+    .await?;
+
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_response(
+            interaction.id,
+            &interaction.token,
+            &InteractionResponse {
+                kind: InteractionResponseType::UpdateMessage,
+                data: Some(
+                    InteractionResponseDataBuilder::new()
+                        .content(decision)
+                        .components(Vec::new())
+                        .build(),
+                ),
+            },
+        )
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+async fn handle_waitlist_command(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &InteractionCreate,
+    interaction_data: &CommandData,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut guild_name = None;
+    for opt in &interaction_data.options {
+        if opt.name == "guild" {
+            if let CommandOptionValue::String(opt_str) = &opt.value {
+                guild_name = Some(opt_str.to_string());
+            }
+        }
+    }
+
+    let mut config = config.lock().await;
+    let pool = config.database.get().await?;
+
+    let db_res_vec = if let Some(guild_name) = guild_name {
+        let guild = config
+            .hypixel_api
+            .get_guild_by_name(&guild_name)
+            .await?
+            .guild
+            .ok_or("Invalid guild")?;
+        pool.query(
+            "SELECT * FROM waitlist WHERE guild_id = $1 ORDER BY id",
+            &[&guild.id],
+        )
+        .await?
+    } else {
+        pool.query("SELECT * FROM waitlist ORDER BY guild_id, id", &[])
+            .await?
+    };
+
+    let mut out = String::new();
+    let mut cur_guild_id = String::new();
+    let mut position = 0;
+    for db_res in &db_res_vec {
+        let guild_id: String = db_res.get("guild_id");
+        let uuid: String = db_res.get("uuid");
+        let discord: String = db_res.get("discord");
+        let notified: Option<i64> = db_res.get("notified");
+
+        if guild_id != cur_guild_id {
+            let guild_res = config.hypixel_api.get_guild_by_id(&guild_id).await?;
+            write!(
+                out,
+                "\n**{}**",
+                guild_res.guild.map_or(guild_id.clone(), |guild| guild.name)
+            )?;
+            cur_guild_id = guild_id;
+            position = 0;
+        }
+        position += 1;
+
+        write!(
+            out,
+            "\n{}. [{}](https://mine.ly/{}) <@{}>{}",
+            position,
+            get_username(&config, &pool, &uuid).await,
+            uuid,
+            discord,
+            notified.map_or(String::new(), |notified| format!(
+                " (slot open until <t:{}:f>)",
+                (notified + WAITLIST_CLAIM_MILLIS) / 1000
+            ))
+        )?;
+    }
+
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_followup(&interaction.token)
+        .embeds(&[default_embed("Waitlist")
+            .description(if out.is_empty() {
+                "The waitlist is empty".to_string()
+            } else {
+                out
+            })
+            .build()])?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+async fn handle_roles_command(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &InteractionCreate,
+    interaction_data: &CommandData,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let guild_id = interaction.guild_id.unwrap();
+    let mut user_id = interaction
+        .member
+        .as_ref()
+        .unwrap()
+        .user
+        .as_ref()
+        .unwrap()
+        .id;
+    for opt in &interaction_data.options {
+        if opt.name == "update" {
+            if let CommandOptionValue::SubCommand(sub_opts) = &opt.value {
+                for sub_opt in sub_opts {
+                    if sub_opt.name == "user" {
+                        if let CommandOptionValue::User(opt_user) = &sub_opt.value {
+                            user_id = *opt_user;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut config = config.lock().await;
+    let pool = config.database.get().await?;
+    let server_config = ServerConfig::read_config(&pool).await;
+    let accounts = LinkedAccount::get_by_discord(&pool, &user_id.to_string()).await?;
+    if accounts.is_empty() {
+        return Err("User is not linked".into());
+    }
+
+    let member_roles = http
+        .guild_member(guild_id, user_id)
+        .exec()
+        .await?
+        .model()
+        .await?
+        .roles;
+    let account_stats = get_account_stats(&mut config, &accounts).await;
+    let earned_roles = apply_stat_roles(
+        http,
+        &server_config,
+        guild_id,
+        user_id,
+        &account_stats,
+        &member_roles,
+    )
+    .await?;
+
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_followup(&interaction.token)
+        .embeds(&[default_embed("Stat Roles")
+            .description(if earned_roles.is_empty() {
+                format!("<@{}> does not qualify for any stat roles", user_id)
+            } else {
+                format!(
+                    "Updated stat roles for <@{}>: {}",
+                    user_id,
+                    earned_roles
+                        .iter()
+                        .map(|role| format!("<@&{}>", role))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+            .build()])?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+async fn handle_progress_command(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &InteractionCreate,
+    interaction_data: &CommandData,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut player = String::new();
+    let mut days = 7;
+    for opt in &interaction_data.options {
+        if opt.name == "player" {
+            if let CommandOptionValue::String(opt_str) = &opt.value {
+                player = opt_str.to_string();
+            }
+        } else if opt.name == "days" {
+            if let CommandOptionValue::Integer(opt_int) = &opt.value {
+                days = *opt_int;
+            }
+        }
+    }
+
+    let mut config = config.lock().await;
+    let pool = config.database.get().await?;
+
+    let uuid_res = config.hypixel_api.username_to_uuid(&player).await?;
+    let sb_response = config
+        .hypixel_api
+        .get_skyblock_profiles_by_uuid(&uuid_res.uuid)
+        .await?;
+    let current = get_player_stats(
+        sb_response
+            .get_last_played_profile()
+            .ok_or("No profile found")?,
+    );
+
+    // The baseline is read before caching so a first lookup doesn't compare against itself
+    let since = get_timestamp_millis() - days * 24 * 60 * 60 * 1000;
+    let db_res_vec = pool
+        .query(
+            "SELECT * FROM stat_snapshots WHERE uuid = $1 AND time >= $2 ORDER BY time LIMIT 1",
+            &[&uuid_res.uuid, &since],
+        )
+        .await?;
+    cache_player_stats(&pool, &uuid_res.uuid, &uuid_res.username, &current).await?;
+    let db_res = db_res_vec
+        .first()
+        .ok_or("No snapshots for this player yet, check back later")?;
+    let start_time: i64 = db_res.get("time");
+    let start = PlayerStats::from_row(db_res);
+
+    let mut out = format!(
+        "[{}](https://mine.ly/{}) since <t:{}:R>",
+        uuid_res.username,
+        uuid_res.uuid,
+        start_time / 1000
+    );
+    let mut card_rows = Vec::new();
+    for (name, stat_name) in [
+        ("weight", "Weight"),
+        ("skills", "Skill Average"),
+        ("catacombs", "Catacombs"),
+        ("slayer", "Slayer XP"),
+        ("level", "Skyblock Level"),
+    ] {
+        let start_value = start.get(name).unwrap_or(0.0);
+        let current_value = current.get(name).unwrap_or(0.0);
+        let (start_str, current_str, gain_str) = if name == "slayer" {
+            (
+                format!("{:.0}", start_value),
+                format!("{:.0}", current_value),
+                format!("{:+.0}", current_value - start_value),
+            )
+        } else {
+            (
+                format!("{:.2}", start_value),
+                format!("{:.2}", current_value),
+                format!("{:+.2}", current_value - start_value),
+            )
+        };
+        write!(
+            out,
+            "\n{}: {} → {} ({})",
+            stat_name, start_str, current_str, gain_str
+        )?;
+        card_rows.push((
+            stat_name.to_string(),
+            format!(
+                "{} ({}{})",
+                format_compact(current_value),
+                if current_value >= start_value {
+                    "+"
+                } else {
+                    ""
+                },
+                format_compact(current_value - start_value)
+            ),
+        ));
+    }
+
+    let mut weight_points: Vec<(String, f64)> = pool
+        .query(
+            "SELECT time, weight FROM stat_snapshots WHERE uuid = $1 AND time >= $2 ORDER BY time",
+            &[&uuid_res.uuid, &since],
+        )
+        .await?
+        .iter()
+        .map(|db_res| {
+            (
+                format_date(db_res.get("time"))[5..].to_string(),
+                db_res.get("weight"),
+            )
+        })
+        .collect();
+    weight_points.push(("Now".to_string(), current.weight));
+
+    let mut attachments = Vec::new();
+    let mut eb = default_embed("Progress").description(out);
+    if let Some(chart) =
+        render_line_chart(&format!("Weight - {}", uuid_res.username), &weight_points)
+    {
+        attachments.push(Attachment::from_bytes("weight.png".to_string(), chart, 0));
+        eb = eb.image(ImageSource::attachment("weight.png")?);
+    }
+    if let Some(card) = render_stat_card(&uuid_res.username, &card_rows) {
+        attachments.push(Attachment::from_bytes("progress.png".to_string(), card, 1));
+    }
+
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_followup(&interaction.token)
+        .embeds(&[eb.build()])?
+        .attachments(&attachments)?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+const PROFILE_BATCH_SIZE: usize = 10;
+
+async fn handle_competition_command(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &InteractionCreate,
+    interaction_data: &CommandData,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let subcommand = interaction_data
+        .options
+        .first()
+        .ok_or("Invalid subcommand")?;
+
+    let active = {
+        let config = config.lock().await;
+        let pool = config.database.get().await?;
+        pool.query(
+            "SELECT * FROM competitions WHERE NOT ended ORDER BY id DESC LIMIT 1",
+            &[],
+        )
+        .await?
+        .into_iter()
+        .next()
+    };
+
+    if subcommand.name != "leaderboard" && !is_staff(interaction) {
+        return Err("Only staff can start or end competitions".into());
+    }
+
+    let eb = match subcommand.name.as_str() {
+        "start" => {
+            if active.is_some() {
+                return Err("A competition is already running".into());
+            }
+
+            let mut stat = String::new();
+            let mut duration = String::new();
+            let mut guild_name = None;
+            if let CommandOptionValue::SubCommand(sub_opts) = &subcommand.value {
+                for sub_opt in sub_opts {
+                    if let CommandOptionValue::String(opt_str) = &sub_opt.value {
+                        match sub_opt.name.as_str() {
+                            "stat" => stat = opt_str.to_string(),
+                            "duration" => duration = opt_str.to_string(),
+                            "guild" => guild_name = Some(opt_str.to_string()),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            let duration = parse_duration(&duration)
+                .ok_or("Invalid duration, use a number followed by m, h, d or w up to 52w")?;
+
+            let (pool, guild, uuids) = {
+                let mut config = config.lock().await;
+                let pool = config.database.get().await?;
+                let guild = match guild_name {
+                    Some(guild_name) => Some(
+                        config
+                            .hypixel_api
+                            .get_guild_by_name(&guild_name)
+                            .await?
+                            .guild
+                            .ok_or("Invalid guild")?,
+                    ),
+                    None => None,
+                };
+                let uuids: Vec<String> = match &guild {
+                    Some(guild) => guild
+                        .members
+                        .iter()
+                        .map(|guild_member| guild_member.uuid.clone())
+                        .collect(),
+                    None => pool
+                        .query("SELECT uuid FROM linked_accounts", &[])
+                        .await?
+                        .iter()
+                        .map(|db_res| db_res.get("uuid"))
+                        .collect(),
+                };
+                (pool, guild, uuids)
+            };
+
+            let start_time = get_timestamp_millis();
+            let end_time = start_time.checked_add(duration).ok_or("Invalid duration")?;
+            let competition = pool
+                .query_one(
+                    "INSERT INTO competitions (stat, guild_id, guild_name, channel, start_time, end_time, updated) VALUES ($1, $2, $3, $4, $5, $6, $5) RETURNING *",
+                    &[
+                        &stat,
+                        &guild.as_ref().map(|guild| guild.id.clone()),
+                        &guild.as_ref().map(|guild| guild.name.clone()),
+                        &interaction.channel_id.map(|channel_id| channel_id.to_string()),
+                        &start_time,
+                        &end_time,
+                    ],
+                )
+                .await?;
+            let id: i32 = competition.get("id");
+
+            // The lock is released between batches so other commands can use the API
+            let mut participants = 0;
+            for batch in uuids.chunks(PROFILE_BATCH_SIZE) {
+                let mut config = config.lock().await;
+                for uuid in batch {
+                    let sb_response =
+                        match config.hypixel_api.get_skyblock_profiles_by_uuid(uuid).await {
+                            Ok(sb_response) => sb_response,
+                            Err(_) => continue,
+                        };
+                    if let Some(value) = sb_response
+                        .get_last_played_profile()
+                        .and_then(|sb_profile| get_competition_value(sb_profile, &stat))
+                    {
+                        pool.query(
+                            "INSERT INTO competition_entries (competition_id, uuid, start_value, current_value) VALUES ($1, $2, $3, $3) ON CONFLICT DO NOTHING",
+                            &[&id, uuid, &value],
+                        )
+                        .await?;
+                        participants += 1;
+                    }
+                }
+            }
+
+            default_embed(&get_competition_title(&competition)).description(format!(
+                "Started with {} participants, ends <t:{}:R>",
+                participants,
+                end_time / 1000
+            ))
+        }
+        "leaderboard" => {
+            let competition = active.ok_or("No competition is running")?;
+            let id: i32 = competition.get("id");
+            let updated: i64 = competition.get("updated");
+            let end_time: i64 = competition.get("end_time");
+
+            let config = config.lock().await;
+            let pool = config.database.get().await?;
+            default_embed(&get_competition_title(&competition)).description(format!(
+                "Ends <t:{}:R>, updated <t:{}:R>\n{}",
+                end_time / 1000,
+                updated / 1000,
+                build_competition_standings(&config, &pool, id, 10).await?
+            ))
+        }
+        "end" => {
+            let competition = active.ok_or("No competition is running")?;
+            let id: i32 = competition.get("id");
+            refresh_competition(&config, id, competition.get("stat")).await?;
+            end_competition(http, &config, id).await?;
+            default_embed(&get_competition_title(&competition)).description("Ended the competition")
+        }
+        _ => return Err("Invalid subcommand".into()),
+    };
+
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_followup(&interaction.token)
+        .embeds(&[eb.build()])?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+/// Stat option value to display name
+const LEADERBOARD_STATS: [(&str, &str); 6] = [
+    ("weight", "Weight"),
+    ("skills", "Skill Average"),
+    ("catacombs", "Catacombs"),
+    ("slayer", "Slayer XP"),
+    ("level", "Skyblock Level"),
+    ("gexp", "Weekly GEXP"),
+];
+const LEADERBOARD_PAGE_SIZE: i64 = 10;
+
+async fn handle_leaderboard_command(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &InteractionCreate,
+    interaction_data: &CommandData,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut stat = String::new();
+    let mut guild_name = None;
+    for opt in &interaction_data.options {
+        if opt.name == "stat" {
+            if let CommandOptionValue::String(opt_str) = &opt.value {
+                stat = opt_str.to_string();
+            }
+        } else if opt.name == "guild" {
+            if let CommandOptionValue::String(opt_str) = &opt.value {
+                guild_name = Some(opt_str.to_string());
+            }
+        }
+    }
+
+    let mut config = config.lock().await;
+    let guild = match guild_name {
+        Some(guild_name) => Some(
+            config
+                .hypixel_api
+                .get_guild_by_name(&guild_name)
+                .await?
+                .guild
+                .ok_or("Invalid guild")?,
+        ),
+        None => None,
+    };
+
+    let (embed, components) = build_leaderboard(&config, &stat, guild.as_ref(), 0).await?;
+
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_followup(&interaction.token)
+        .embeds(&[embed])?
+        .components(&components)?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+async fn handle_leaderboard_button(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &InteractionCreate,
+    component_data: &MessageComponentInteractionData,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    // leaderboard:<stat>:<guild id or all>:<page>
+    let parts: Vec<&str> = component_data.custom_id.split(':').collect();
+    if parts.len() != 4 {
+        return Err("Invalid leaderboard button".into());
+    }
+    let page: i64 = parts[3].parse()?;
+
+    let mut config = config.lock().await;
+    let guild = if parts[2] == "all" {
+        None
+    } else {
+        config.hypixel_api.get_guild_by_id(parts[2]).await?.guild
+    };
+
+    let (embed, components) = build_leaderboard(&config, parts[1], guild.as_ref(), page).await?;
+
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_response(
+            interaction.id,
+            &interaction.token,
+            &InteractionResponse {
+                kind: InteractionResponseType::UpdateMessage,
+                data: Some(
+                    InteractionResponseDataBuilder::new()
+                        .embeds([embed])
+                        .components(components)
+                        .build(),
+                ),
+            },
+        )
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+/// Builds one page of a leaderboard from the stats cache
+async fn build_leaderboard(
+    config: &Config,
+    stat: &str,
+    guild: Option<&Guild>,
+    page: i64,
+) -> Result<(Embed, Vec<Component>), Box<dyn Error + Send + Sync>> {
+    let stat_name = LEADERBOARD_STATS
+        .iter()
+        .find(|(stat_value, _)| *stat_value == stat)
+        .map(|(_, stat_name)| *stat_name)
+        .ok_or("Invalid stat")?;
+    let pool = config.database.get().await?;
+
+    let uuids: Vec<String> = match guild {
+        Some(guild) => guild
+            .members
+            .iter()
+            .map(|guild_member| guild_member.uuid.clone())
+            .collect(),
+        None => pool
+            .query("SELECT uuid FROM linked_accounts", &[])
+            .await?
+            .iter()
+            .map(|db_res| db_res.get("uuid"))
+            .collect(),
+    };
+
+    let db_res_vec = if stat == "gexp" {
+        // Only exp earned in the guild being ranked or the configured guilds counts
+        let guild_ids = match guild {
+            Some(guild) => vec![guild.id.clone()],
+            None => {
+                get_configured_guild_ids(&pool, &ServerConfig::read_config(&pool).await).await?
+            }
+        };
+        pool.query(
+            "SELECT uuid, SUM(exp)::DOUBLE PRECISION AS value, COUNT(*) OVER () AS total FROM guild_exp WHERE uuid = ANY($1) AND guild_id = ANY($4) AND date::date > CURRENT_DATE - 7 GROUP BY uuid ORDER BY value DESC OFFSET $2 LIMIT $3",
+            &[
+                &uuids,
+                &(page * LEADERBOARD_PAGE_SIZE),
+                &LEADERBOARD_PAGE_SIZE,
+                &guild_ids,
+            ],
+        )
+        .await?
+    } else {
+        // The column name comes from LEADERBOARD_STATS so it is safe to format into the query
+        pool.query(
+            &format!(
+                "SELECT uuid, {}::DOUBLE PRECISION AS value, COUNT(*) OVER () AS total FROM player_stats WHERE uuid = ANY($1) ORDER BY value DESC OFFSET $2 LIMIT $3",
+                stat
+            ),
+            &[
+                &uuids,
+                &(page * LEADERBOARD_PAGE_SIZE),
+                &LEADERBOARD_PAGE_SIZE,
+            ],
+        )
+        .await?
+    };
+    let total: i64 = db_res_vec.first().map_or(0, |db_res| db_res.get("total"));
+    let page_count = ((total + LEADERBOARD_PAGE_SIZE - 1) / LEADERBOARD_PAGE_SIZE).max(1);
+
+    let mut out = String::new();
+    for (idx, db_res) in db_res_vec.iter().enumerate() {
+        let uuid: String = db_res.get("uuid");
+        let value: f64 = db_res.get("value");
+        write!(
+            out,
+            "\n{}. [{}](https://mine.ly/{}): {}",
+            page * LEADERBOARD_PAGE_SIZE + idx as i64 + 1,
+            get_username(config, &pool, &uuid).await,
+            uuid,
+            if value.fract() == 0.0 || value >= 1000.0 {
+                format!("{:.0}", value)
+            } else {
+                format!("{:.2}", value)
+            }
+        )?;
+    }
+
+    let embed = default_embed(&format!(
+        "{} Leaderboard - {}",
+        stat_name,
+        guild.map_or("Linked Members", |guild| guild.name.as_str())
+    ))
+    .description(if out.is_empty() {
+        "No cached stats yet".to_string()
+    } else {
+        format!("Page {}/{}{}", page + 1, page_count, out)
+    })
+    .build();
+
+    let guild_id = guild.map_or("all", |guild| guild.id.as_str());
+    let components = vec![Component::ActionRow(ActionRow {
+        components: vec![
+            Component::Button(Button {
+                custom_id: Some(format!("leaderboard:{}:{}:{}", stat, guild_id, page - 1)),
+                disabled: page == 0,
+                emoji: None,
+                label: Some("Previous".to_string()),
+                style: ButtonStyle::Secondary,
+                url: None,
+            }),
+            Component::Button(Button {
+                custom_id: Some(format!("leaderboard:{}:{}:{}", stat, guild_id, page + 1)),
+                disabled: page + 1 >= page_count,
+                emoji: None,
+                label: Some("Next".to_string()),
+                style: ButtonStyle::Secondary,
+                url: None,
+            }),
+        ],
+    })];
+
+    Ok((embed, components))
+}
+
+/// Stat name and value pairs for one `/compare` group. Networth isn't compared since
+/// valuing inventories and items is out of scope.
+fn get_compare_stats(profile: &SkyblockProfile, group: &str) -> Vec<(String, f64)> {
+    match group {
+        "skills" => get_skill_names()
+            .into_iter()
+            .map(|name| (name.to_string(), get_skill_level(profile, name)))
+            .collect(),
+        "slayer" => ["zombie", "spider", "wolf", "enderman", "blaze"]
+            .into_iter()
+            .map(|name| {
+                (
+                    name.to_string(),
+                    profile
+                        .get_slayer(name)
+                        .map_or(0.0, |slayer_boss| slayer_boss.total_exp as f64),
+                )
+            })
+            .collect(),
+        "dungeons" => get_dungeon_levels(profile)
+            .into_iter()
+            .map(|(name, level)| (name.to_string(), level))
+            .collect(),
+        _ => {
+            let stats = get_player_stats(profile);
+            PlayerStats::NAMES
+                .into_iter()
+                .map(|name| (name.to_string(), stats.get(name).unwrap_or(0.0)))
+                .collect()
+        }
+    }
+}
+
+async fn handle_compare_command(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &InteractionCreate,
+    interaction_data: &CommandData,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut players = [String::new(), String::new()];
+    let mut group = "overview".to_string();
+    for opt in &interaction_data.options {
+        if let CommandOptionValue::String(opt_str) = &opt.value {
+            match opt.name.as_str() {
+                "player1" => players[0] = opt_str.to_string(),
+                "player2" => players[1] = opt_str.to_string(),
+                "group" => group = opt_str.to_string(),
+                _ => {}
+            }
+        }
+    }
+
+    let mut config = config.lock().await;
+    let mut usernames = Vec::new();
+    let mut all_stats = Vec::new();
+    for player in &players {
+        let uuid_res = config.hypixel_api.username_to_uuid(player).await?;
+        let sb_response = config
+            .hypixel_api
+            .get_skyblock_profiles_by_uuid(&uuid_res.uuid)
+            .await?;
+        let sb_profile = sb_response
+            .get_last_played_profile()
+            .ok_or(format!("No profile found for {}", uuid_res.username))?;
+        all_stats.push(get_compare_stats(sb_profile, &group));
+        usernames.push(uuid_res.username);
+    }
+
+    let mut names_out = String::new();
+    let mut first_out = String::new();
+    let mut second_out = String::new();
+    for ((name, first), (_, second)) in all_stats[0].iter().zip(&all_stats[1]) {
+        let format_value = |value: f64| {
+            if value >= 1000.0 {
+                format_compact(value)
+            } else {
+                format!("{:.2}", value)
+            }
+        };
+        // Bold whoever is ahead and show by how much
+        let diff = format_value((first - second).abs());
+        write!(names_out, "\n{}", name)?;
+        if first > second {
+            write!(first_out, "\n**{}** (+{})", format_value(*first), diff)?;
+            write!(second_out, "\n{}", format_value(*second))?;
+        } else if second > first {
+            write!(first_out, "\n{}", format_value(*first))?;
+            write!(second_out, "\n**{}** (+{})", format_value(*second), diff)?;
+        } else {
+            write!(first_out, "\n{}", format_value(*first))?;
+            write!(second_out, "\n{}", format_value(*second))?;
+        }
+    }
+
+    let eb = default_embed(&format!("{} vs {}", usernames[0], usernames[1]))
+        .field(EmbedFieldBuilder::new("Stat", names_out).inline().build())
+        .field(
+            EmbedFieldBuilder::new(&usernames[0], first_out)
+                .inline()
+                .build(),
+        )
+        .field(
+            EmbedFieldBuilder::new(&usernames[1], second_out)
+                .inline()
+                .build(),
+        );
+
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_followup(&interaction.token)
+        .embeds(&[eb.build()])?
+        .exec()
         .await?;
 
     Ok(())
 }
 
-async fn handle_reqs_command(
+async fn handle_reconcile_command(
     http: &Arc<HttpClient>,
     config: Arc<Mutex<Config>>,
-    interaction: &Box<InteractionCreate>,
-    interaction_data: &Box<CommandData>,
+    interaction: &InteractionCreate,
+    _interaction_data: &CommandData,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let guild_id = interaction.guild_id.unwrap();
+
+    let (server_config, linked_users) = {
+        let config = config.lock().await;
+        let pool = config.database.get().await?;
+        let linked_users: HashSet<String> = pool
+            .query("SELECT DISTINCT discord FROM linked_accounts", &[])
+            .await?
+            .iter()
+            .map(|row| row.get("discord"))
+            .collect();
+        (ServerConfig::read_config(&pool).await, linked_users)
+    };
+    let verified_role = Id::from_str(&server_config.verified_role)?;
+    let unverified_role: Id<RoleMarker> = Id::from_str(&server_config.unverified_role)
+        .map_err(|_| "The unverified role is not set")?;
+
+    let mut fixed_verified = 0;
+    let mut fixed_unverified = 0;
+    let mut failed = Vec::new();
+    for member in get_all_members(http, guild_id).await? {
+        if member.user.bot {
+            continue;
+        }
+
+        let has_verified = member.roles.contains(&verified_role);
+        let has_unverified = member.roles.contains(&unverified_role);
+        if has_verified != has_unverified {
+            continue;
+        }
+
+        let linked = linked_users.contains(&member.user.id.to_string());
+        let (add_role, remove_role) = if linked {
+            (verified_role, unverified_role)
+        } else {
+            (unverified_role, verified_role)
+        };
+        match swap_member_roles(http, guild_id, &member, add_role, remove_role).await {
+            Ok(()) if linked => fixed_verified += 1,
+            Ok(()) => fixed_unverified += 1,
+            Err(err) => {
+                println!("Error reconciling roles of {}: {}", member.user.id, err);
+                failed.push(format!("<@{}>", member.user.id));
+            }
+        }
+    }
+
+    let mut out = format!(
+        "Set {} linked members to verified and {} unlinked members to unverified",
+        fixed_verified, fixed_unverified
+    );
+    if !failed.is_empty() {
+        write!(
+            out,
+            "\n\nCould not update {} members:\n{}",
+            failed.len(),
+            format_sweep_list(&failed)
+        )?;
+    }
+
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_followup(&interaction.token)
+        .embeds(&[default_embed("Reconcile").description(out).build()])?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+/// Adds a role the member is missing and removes one they shouldn't have
+async fn swap_member_roles(
+    http: &Arc<HttpClient>,
+    guild_id: Id<GuildMarker>,
+    member: &Member,
+    add_role: Id<RoleMarker>,
+    remove_role: Id<RoleMarker>,
+) -> Result<(), twilight_http::Error> {
+    if !member.roles.contains(&add_role) {
+        http.add_guild_member_role(guild_id, member.user.id, add_role)
+            .exec()
+            .await?;
+    }
+    if member.roles.contains(&remove_role) {
+        http.remove_guild_member_role(guild_id, member.user.id, remove_role)
+            .exec()
+            .await?;
+    }
+
+    Ok(())
+}
+
+const LOGIN_CACHE_MILLIS: i64 = 12 * 60 * 60 * 1000;
+const STATUS_CACHE_MILLIS: i64 = 3 * 60 * 1000;
+const STATUS_BATCH_SIZE: usize = 10;
+
+/// Looks up a guild by name, falling back to the guild of a player
+async fn find_guild(
+    config: &mut Config,
+    query: &str,
+) -> Result<Guild, Box<dyn Error + Send + Sync>> {
+    match config.hypixel_api.get_guild_by_name(query).await?.guild {
+        Some(guild) => Ok(guild),
+        None => {
+            let uuid_res = config
+                .hypixel_api
+                .username_to_uuid(query)
+                .await
+                .map_err(|_| "No guild or player found")?;
+            Ok(config
+                .hypixel_api
+                .get_guild_by_player(&uuid_res.uuid)
+                .await?
+                .guild
+                .ok_or(format!("{} is not in a guild", uuid_res.username))?)
+        }
+    }
+}
+
+/// Statuses for the players and the errors of lookups that failed, only requesting players
+/// not cached in the last few minutes. Hypixel has no bulk status endpoint, so requests are
+/// sent one at a time and the lock is released after every batch so other commands can
+/// use the API in between.
+async fn get_statuses(
+    config: &Arc<Mutex<Config>>,
+    pool: &Object,
+    uuids: &[String],
+) -> Result<(HashMap<String, PlayerStatus>, HashMap<String, String>), Box<dyn Error + Send + Sync>>
+{
+    let mut statuses: HashMap<String, PlayerStatus> = pool
+        .query(
+            "SELECT * FROM player_status WHERE uuid = ANY($1) AND updated > $2",
+            &[&uuids, &(get_timestamp_millis() - STATUS_CACHE_MILLIS)],
+        )
+        .await?
+        .iter()
+        .map(|row| (row.get("uuid"), PlayerStatus::from_row(row)))
+        .collect();
+
+    let missing: Vec<&String> = uuids
+        .iter()
+        .filter(|uuid| !statuses.contains_key(*uuid))
+        .collect();
+    let mut failed = HashMap::new();
+    for batch in missing.chunks(STATUS_BATCH_SIZE) {
+        let mut config = config.lock().await;
+        for uuid in batch {
+            let session = match config.hypixel_api.get_status(uuid).await {
+                Ok(status_res) => status_res.session,
+                Err(err) => {
+                    failed.insert(uuid.to_string(), err.to_string());
+                    continue;
+                }
+            };
+            let status = PlayerStatus {
+                online: session.online,
+                game: session.game_type.map(|server_type| match server_type {
+                    ServerType::GameType(game_type) => game_type.name(),
+                    ServerType::LobbyType(lobby_type) => lobby_type.name(),
+                    ServerType::Unknown => "Unknown".to_string(),
+                }),
+                mode: session.mode,
+                map: session.map,
+            };
+            pool.query(
+                "INSERT INTO player_status (uuid, online, game, mode, map, updated) VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT (uuid) DO UPDATE SET online = EXCLUDED.online, game = EXCLUDED.game, mode = EXCLUDED.mode, map = EXCLUDED.map, updated = EXCLUDED.updated",
+                &[
+                    uuid,
+                    &status.online,
+                    &status.game,
+                    &status.mode,
+                    &status.map,
+                    &get_timestamp_millis(),
+                ],
+            )
+            .await?;
+            statuses.insert(uuid.to_string(), status);
+        }
+    }
+
+    Ok((statuses, failed))
+}
+
+/// Last logins for the players and the number of lookups that failed, only requesting players
+/// not cached in the last few hours. Requests are batched the same way as statuses.
+async fn get_last_logins(
+    config: &Arc<Mutex<Config>>,
+    pool: &Object,
+    uuids: &[String],
+) -> Result<(HashMap<String, PlayerLogin>, usize), Box<dyn Error + Send + Sync>> {
+    let mut logins: HashMap<String, PlayerLogin> = pool
+        .query(
+            "SELECT * FROM player_logins WHERE uuid = ANY($1) AND updated > $2",
+            &[&uuids, &(get_timestamp_millis() - LOGIN_CACHE_MILLIS)],
+        )
+        .await?
+        .iter()
+        .map(|row| (row.get("uuid"), PlayerLogin::from_row(row)))
+        .collect();
+
+    let missing: Vec<&String> = uuids
+        .iter()
+        .filter(|uuid| !logins.contains_key(*uuid))
+        .collect();
+    let mut failed = 0;
+    for batch in missing.chunks(STATUS_BATCH_SIZE) {
+        let mut config = config.lock().await;
+        for uuid in batch {
+            let player_res = match config.hypixel_api.get_player_by_uuid(uuid).await {
+                Ok(player_res) => player_res,
+                Err(err) => {
+                    println!("Error getting last login of {}: {}", uuid, err);
+                    failed += 1;
+                    continue;
+                }
+            };
+            let login = PlayerLogin {
+                username: player_res.get_name().map(|name| name.to_string()),
+                last_login: player_res.get_int_property("lastLogin"),
+            };
+            pool.query(
+                "INSERT INTO player_logins (uuid, username, last_login, updated) VALUES ($1, $2, $3, $4) ON CONFLICT (uuid) DO UPDATE SET username = EXCLUDED.username, last_login = EXCLUDED.last_login, updated = EXCLUDED.updated",
+                &[
+                    uuid,
+                    &login.username,
+                    &login.last_login,
+                    &get_timestamp_millis(),
+                ],
+            )
+            .await?;
+            logins.insert(uuid.to_string(), login);
+        }
+    }
+
+    Ok((logins, failed))
+}
+
+async fn handle_status_command(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &InteractionCreate,
+    interaction_data: &CommandData,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
     let mut player = String::new();
     for opt in &interaction_data.options {
@@ -245,350 +4849,900 @@ async fn handle_reqs_command(
         }
     }
 
-    let mut profile = String::new();
+    let (uuid_res, pool) = {
+        let config = config.lock().await;
+        (
+            config.hypixel_api.username_to_uuid(&player).await?,
+            config.database.get().await?,
+        )
+    };
+    let (mut statuses, mut failed) =
+        get_statuses(&config, &pool, std::slice::from_ref(&uuid_res.uuid)).await?;
+    if let Some(err) = failed.remove(&uuid_res.uuid) {
+        return Err(err.into());
+    }
+    let status = statuses
+        .remove(&uuid_res.uuid)
+        .ok_or(format!("Unable to get the status of {}", uuid_res.username))?;
+
+    let eb = default_embed(&format!("Status of {}", uuid_res.username))
+        .url(format!("https://mine.ly/{}", uuid_res.uuid))
+        .description(if status.online {
+            format!("Online\n{}", status.get_location())
+        } else {
+            "Offline".to_string()
+        });
+
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_followup(&interaction.token)
+        .embeds(&[eb.build()])?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+async fn handle_online_command(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &InteractionCreate,
+    interaction_data: &CommandData,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut query = String::new();
     for opt in &interaction_data.options {
-        if opt.name == "profile" {
+        if opt.name == "guild" {
             if let CommandOptionValue::String(opt_str) = &opt.value {
-                profile = opt_str.to_string();
+                query = opt_str.to_string();
             }
         }
     }
 
-    let mut config = config.lock().await;
+    let (guild, pool) = {
+        let mut config = config.lock().await;
+        let pool = config.database.get().await?;
+        (find_guild(&mut config, &query).await?, pool)
+    };
 
-    let uuid_response = config.hypixel_api.username_to_uuid(&player).await?;
-    let sb_response = config
-        .hypixel_api
-        .get_skyblock_profiles_by_uuid(&uuid_response.uuid)
+    // Only members linked in this server are checked to keep the number of requests down
+    let member_uuids: Vec<&String> = guild
+        .members
+        .iter()
+        .map(|guild_member| &guild_member.uuid)
+        .collect();
+    let linked: Vec<LinkedAccount> = pool
+        .query(
+            "SELECT DISTINCT ON (uuid) * FROM linked_accounts WHERE uuid = ANY($1)",
+            &[&member_uuids],
+        )
+        .await?
+        .iter()
+        .map(LinkedAccount::from_row)
+        .collect();
+    let uuids: Vec<String> = linked.iter().map(|account| account.uuid.clone()).collect();
+    let (statuses, failed) = get_statuses(&config, &pool, &uuids).await?;
+
+    let online_lines: Vec<String> = linked
+        .iter()
+        .filter_map(|account| {
+            statuses
+                .get(&account.uuid)
+                .filter(|status| status.online)
+                .map(|status| {
+                    format!(
+                        "[{}](https://mine.ly/{}) (<@{}>): {}",
+                        account.username,
+                        account.uuid,
+                        account.discord,
+                        status.get_location()
+                    )
+                })
+        })
+        .collect();
+
+    let eb = default_embed(&format!("Online in {}", guild.name))
+        .description(format!(
+            "{}/{} linked members online{}",
+            online_lines.len(),
+            linked.len() - failed.len(),
+            if failed.is_empty() {
+                String::new()
+            } else {
+                format!("\n{} members could not be checked", failed.len())
+            }
+        ))
+        .field(EmbedFieldBuilder::new("Online", format_sweep_list(&online_lines)).build());
+
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_followup(&interaction.token)
+        .embeds(&[eb.build()])?
+        .exec()
         .await?;
-    let sb_profile = if profile.is_empty() {
-        sb_response.get_last_played_profile()
-    } else {
-        sb_response.get_profile_by_name(&profile)
+
+    Ok(())
+}
+
+async fn handle_guild_command(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &InteractionCreate,
+    interaction_data: &CommandData,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut query = String::new();
+    for opt in &interaction_data.options {
+        if opt.name == "guild" {
+            if let CommandOptionValue::String(opt_str) = &opt.value {
+                query = opt_str.to_string();
+            }
+        }
+    }
+
+    let mut config = config.lock().await;
+    let pool = config.database.get().await?;
+
+    let guild = find_guild(&mut config, &query).await?;
+    record_guild_exp(&pool, &guild).await?;
+
+    let member_uuids: Vec<&String> = guild
+        .members
+        .iter()
+        .map(|guild_member| &guild_member.uuid)
+        .collect();
+    let linked: i64 = pool
+        .query_one(
+            "SELECT COUNT(DISTINCT uuid) FROM linked_accounts WHERE uuid = ANY($1)",
+            &[&member_uuids],
+        )
+        .await?
+        .get(0);
+
+    let mut ranks: Vec<_> = guild.ranks.iter().collect();
+    ranks.sort_by_key(|rank| Reverse(rank.priority));
+    let mut ranks_out = String::new();
+    for rank in ranks {
+        let count = guild
+            .members
+            .iter()
+            .filter(|guild_member| normalize_rank(&guild_member.rank) == normalize_rank(&rank.name))
+            .count();
+        write!(
+            ranks_out,
+            "\n{}{}: {}",
+            rank.name,
+            if rank.tag.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", rank.tag)
+            },
+            count
+        )?;
+    }
+
+    let mut weekly: Vec<(&String, i64)> = guild
+        .members
+        .iter()
+        .map(|guild_member| (&guild_member.uuid, guild_member.exp_history.values().sum()))
+        .collect();
+    weekly.sort_by_key(|(_, exp)| Reverse(*exp));
+    let mut top_out = String::new();
+    for (idx, (uuid, exp)) in weekly.iter().take(10).enumerate() {
+        write!(
+            top_out,
+            "\n{}. [{}](https://mine.ly/{}): {}",
+            idx + 1,
+            get_username(&config, &pool, uuid).await,
+            uuid,
+            exp
+        )?;
     }
-    .ok_or("No profile found")?;
 
-    let mut eb = default_embed("Requirement Checker");
+    let eb = default_embed(&if guild.tag.is_empty() {
+        guild.name.clone()
+    } else {
+        format!("{} [{}]", guild.name, guild.tag)
+    })
+    .description(format!(
+        "Level: {:.2}\nMembers: {}/{}\nLinked in this server: {}\nWeekly GEXP: {}",
+        get_guild_level(guild.exp),
+        guild.members.len(),
+        GUILD_MEMBER_LIMIT,
+        linked,
+        weekly.iter().map(|(_, exp)| exp).sum::<i64>()
+    ))
+    .field(EmbedFieldBuilder::new("Ranks", ranks_out).inline().build())
+    .field(
+        EmbedFieldBuilder::new("Top Weekly GEXP", top_out)
+            .inline()
+            .build(),
+    );
+
+    let _ = http
+        .interaction(SELF_USER_ID.lock().await.unwrap())
+        .create_followup(&interaction.token)
+        .embeds(&[eb.build()])?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+async fn handle_gexp_command(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &InteractionCreate,
+    interaction_data: &CommandData,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut query = String::new();
+    for opt in &interaction_data.options {
+        if opt.name == "query" {
+            if let CommandOptionValue::String(opt_str) = &opt.value {
+                query = opt_str.to_string();
+            }
+        }
+    }
 
+    let mut config = config.lock().await;
     let pool = config.database.get().await?;
     let server_config = ServerConfig::read_config(&pool).await;
 
-    for req in server_config.guild_reqs {
-        let cur_reqs = req.1;
-        eb = eb.field(EmbedFieldBuilder::new(req.0, "").build());
+    // Resolved like find_guild, but a player query shows that player's history
+    let query_guild = config.hypixel_api.get_guild_by_name(&query).await?.guild;
+    let (eb, chart) = if let Some(guild) = query_guild {
+        if !is_configured_guild(&server_config, &guild) {
+            return Err(format!("GEXP history isn't tracked for {}", guild.name).into());
+        }
+        record_guild_exp(&pool, &guild).await?;
+
+        let member_uuids: Vec<&String> = guild
+            .members
+            .iter()
+            .map(|guild_member| &guild_member.uuid)
+            .collect();
+        let db_res_vec = pool
+            .query(
+                "SELECT uuid, SUM(exp) FILTER (WHERE date::date > CURRENT_DATE - 7)::BIGINT AS weekly, SUM(exp)::BIGINT AS monthly FROM guild_exp WHERE guild_id = $1 AND uuid = ANY($2) AND date::date > CURRENT_DATE - 30 GROUP BY uuid ORDER BY weekly DESC",
+                &[&guild.id, &member_uuids],
+            )
+            .await?;
+        let weekly_req = server_config
+            .guild_reqs
+            .get(&guild.name.to_lowercase())
+            .map(|reqs| reqs.weekly_gexp)
+            .unwrap_or(0);
+
+        let mut top = String::new();
+        let mut below_req = Vec::new();
+        for (idx, db_res) in db_res_vec.iter().enumerate() {
+            let uuid: String = db_res.get("uuid");
+            let weekly: i64 = db_res.try_get("weekly").unwrap_or(0);
+            let monthly: i64 = db_res.get("monthly");
+            if idx < 10 {
+                write!(
+                    top,
+                    "\n{}. [{}](https://mine.ly/{}): {} / {}",
+                    idx + 1,
+                    get_username(&config, &pool, &uuid).await,
+                    uuid,
+                    weekly,
+                    monthly
+                )?;
+            }
+            if weekly < weekly_req {
+                below_req.push((uuid, weekly));
+            }
+        }
+
+        let mut out = format!("{} (7 days / 30 days){}", guild.name, top);
+        if weekly_req > 0 {
+            write!(
+                out,
+                "\n\nBelow the weekly requirement of {} ({}):",
+                weekly_req,
+                below_req.len()
+            )?;
+            let mut below_lines = Vec::new();
+            for (uuid, weekly) in below_req {
+                below_lines.push(format!(
+                    "[{}](https://mine.ly/{}): {}",
+                    get_username(&config, &pool, &uuid).await,
+                    uuid,
+                    weekly
+                ));
+            }
+            write!(out, "\n{}", format_sweep_list(&below_lines))?;
+        }
+
+        let points: Vec<(String, f64)> = pool
+            .query(
+                "SELECT date, SUM(exp)::BIGINT AS exp FROM guild_exp WHERE guild_id = $1 AND date::date > CURRENT_DATE - 30 GROUP BY date ORDER BY date",
+                &[&guild.id],
+            )
+            .await?
+            .iter()
+            .map(|db_res| {
+                let date: String = db_res.get("date");
+                (date[5..].to_string(), db_res.get::<_, i64>("exp") as f64)
+            })
+            .collect();
+
+        (
+            default_embed("Guild Experience").description(out),
+            render_line_chart(&format!("Daily GEXP - {}", guild.name), &points),
+        )
+    } else {
+        let uuid_res = config
+            .hypixel_api
+            .username_to_uuid(&query)
+            .await
+            .map_err(|_| "No guild or player found")?;
+        let guild = config
+            .hypixel_api
+            .get_guild_by_player(&uuid_res.uuid)
+            .await?
+            .guild
+            .ok_or(format!("{} is not in a guild", uuid_res.username))?;
+        if !is_configured_guild(&server_config, &guild) {
+            return Err(format!("GEXP history isn't tracked for {}", guild.name).into());
+        }
+        record_guild_exp(&pool, &guild).await?;
+
+        let db_res_vec = pool
+            .query(
+                "SELECT date, exp FROM guild_exp WHERE guild_id = $1 AND uuid = $2 AND date::date > CURRENT_DATE - 30 ORDER BY date DESC",
+                &[&guild.id, &uuid_res.uuid],
+            )
+            .await?;
+        let mut weekly = 0;
+        let mut monthly = 0;
+        let mut daily = String::new();
+        for (idx, db_res) in db_res_vec.iter().enumerate() {
+            let date: String = db_res.get("date");
+            let exp: i64 = db_res.get("exp");
+            if idx < 7 {
+                weekly += exp;
+                write!(daily, "\n{}: {}", date, exp)?;
+            }
+            monthly += exp;
+        }
+
+        let points: Vec<(String, f64)> = db_res_vec
+            .iter()
+            .rev()
+            .map(|db_res| {
+                let date: String = db_res.get("date");
+                (date[5..].to_string(), db_res.get::<_, i64>("exp") as f64)
+            })
+            .collect();
+
+        (
+            default_embed("Guild Experience").description(format!(
+                "[{}](https://mine.ly/{}) in {}\n7 days: {}\n30 days: {}\n{}",
+                uuid_res.username, uuid_res.uuid, guild.name, weekly, monthly, daily
+            )),
+            render_line_chart(&format!("GEXP - {}", uuid_res.username), &points),
+        )
+    };
+
+    let mut attachments = Vec::new();
+    let mut eb = eb;
+    if let Some(chart) = chart {
+        attachments.push(Attachment::from_bytes("gexp.png".to_string(), chart, 0));
+        eb = eb.image(ImageSource::attachment("gexp.png")?);
     }
 
     let _ = http
         .interaction(SELF_USER_ID.lock().await.unwrap())
         .create_followup(&interaction.token)
         .embeds(&[eb.build()])?
+        .attachments(&attachments)?
         .exec()
         .await?;
 
     Ok(())
 }
 
-async fn handle_verify_command(
+/// Uses the linked username when possible to avoid a Mojang request
+async fn get_username(config: &Config, pool: &Object, uuid: &str) -> String {
+    if let Ok(db_res_vec) = pool
+        .query(
+            "SELECT username FROM linked_accounts WHERE uuid = $1",
+            &[&uuid],
+        )
+        .await
+    {
+        if let Some(db_res) = db_res_vec.first() {
+            return db_res.get("username");
+        }
+    }
+
+    match config.hypixel_api.uuid_to_username(uuid).await {
+        Ok(uuid_res) => uuid_res.username,
+        Err(_) => uuid.to_string(),
+    }
+}
+
+async fn handle_warnings_command(
     http: &Arc<HttpClient>,
     config: Arc<Mutex<Config>>,
-    interaction: &Box<InteractionCreate>,
-    interaction_data: &Box<CommandData>,
+    interaction: &InteractionCreate,
+    interaction_data: &CommandData,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let eb;
-
-    let mut player = String::new();
+    let mut guild_name = None;
     for opt in &interaction_data.options {
-        if opt.name == "player" {
+        if opt.name == "guild" {
             if let CommandOptionValue::String(opt_str) = &opt.value {
-                player = opt_str.to_string();
+                guild_name = Some(opt_str.to_string());
             }
         }
     }
 
     let mut config = config.lock().await;
+    let pool = config.database.get().await?;
 
-    let discord_info = get_discord_info(&mut config, player).await;
-    if let Some(err) = discord_info.error {
-        eb = default_embed("Verify").description(err);
+    let db_res_vec = if let Some(guild_name) = guild_name {
+        let guild = config
+            .hypixel_api
+            .get_guild_by_name(&guild_name)
+            .await?
+            .guild
+            .ok_or("Invalid guild")?;
+        pool.query(
+            "SELECT * FROM req_warnings WHERE guild_id = $1 ORDER BY deadline",
+            &[&guild.id],
+        )
+        .await?
     } else {
-        let user = interaction.member.as_ref().unwrap().user.as_ref().unwrap();
-        let user_tag = format!("{}#{}", user.name, user.discriminator());
-        let api_discord_tag = discord_info.discord.unwrap();
-
-        if api_discord_tag != user_tag {
-            eb = default_embed("Verify").description(format!(
-                "Your Discord tag (`{}`) does not match the in-game Discord tag (`{}`)",
-                user_tag, api_discord_tag
-            ));
-        } else {
-            let user_id = user.id.to_string();
-            let username = discord_info.username.unwrap();
-            let uuid = discord_info.uuid.unwrap();
-
-            let pool = config.database.get().await?;
-            let _ = pool
-                .query(
-                    "DELETE FROM linked_accounts WHERE discord = $1 OR username = $2 or uuid = $3",
-                    &[&user_id, &username, &uuid],
-                )
-                .await;
-            let db_res = pool.query("INSERT INTO linked_accounts (last_updated, discord, username, uuid) VALUES ($1, $2, $3, $4)", &[&get_timestamp_millis(), &user_id, &username, &uuid] ).await;
-
-            if db_res.is_ok() {
-                let server_config = ServerConfig::read_config(&pool).await;
+        pool.query("SELECT * FROM req_warnings ORDER BY deadline", &[])
+            .await?
+    };
 
-                http.add_guild_member_role(
-                    interaction.guild_id.unwrap(),
-                    user.id,
-                    Id::from_str(&server_config.verified_role)?,
-                )
-                .exec()
-                .await?;
+    let mut guild_names: HashMap<String, String> = HashMap::new();
+    let mut out = String::new();
+    for db_res in &db_res_vec {
+        let guild_id: String = db_res.get("guild_id");
+        let uuid: String = db_res.get("uuid");
+        let failing: String = db_res.get("failing");
+        let deadline: i64 = db_res.get("deadline");
 
-                if let Ok(guild_res) = config.hypixel_api.get_guild_by_player(&uuid).await {
-                    if let Some(player_guild) = guild_res.guild {
-                        if let Some(guild_member_role) =
-                            server_config.guild_roles.get(&player_guild.id)
-                        {
-                            http.add_guild_member_role(
-                                interaction.guild_id.unwrap(),
-                                user.id,
-                                Id::from_str(guild_member_role)?,
-                            )
-                            .exec()
-                            .await?;
-                        }
-                    }
-                }
+        if !guild_names.contains_key(&guild_id) {
+            let guild_res = config.hypixel_api.get_guild_by_id(&guild_id).await?;
+            guild_names.insert(
+                guild_id.clone(),
+                guild_res.guild.map_or(guild_id.clone(), |guild| guild.name),
+            );
+        }
 
-                eb = default_embed("Verify")
-                    .description(format!("Successfully linked {} to {}", user_tag, username));
+        let line = format!(
+            "\n[{}](https://mine.ly/{}) in {}: {} ({} <t:{}:R>)",
+            get_username(&config, &pool, &uuid).await,
+            uuid,
+            guild_names[&guild_id],
+            failing,
+            if deadline <= get_timestamp_millis() {
+                "expired"
             } else {
-                eb = default_embed("Verify").description("Error inserting into database");
-            }
+                "expires"
+            },
+            deadline / 1000
+        );
+        if out.len() + line.len() > 4000 {
+            break;
         }
+        out.push_str(&line);
     }
 
     let _ = http
         .interaction(SELF_USER_ID.lock().await.unwrap())
         .create_followup(&interaction.token)
-        .embeds(&[eb.build()])?
+        .embeds(&[default_embed("Requirement Warnings")
+            .description(if db_res_vec.is_empty() {
+                "No active warnings".to_string()
+            } else {
+                format!("{} active warnings{}", db_res_vec.len(), out)
+            })
+            .build()])?
         .exec()
         .await?;
 
     Ok(())
 }
 
-async fn handle_settings_command(
+/// New members haven't had a full week to meet the weekly GEXP requirement
+fn is_new_member(joined: i64, now: i64) -> bool {
+    now - joined < 7 * 24 * 60 * 60 * 1000
+}
+
+struct KicklistEntry {
+    uuid: String,
+    username: String,
+    rank: String,
+    days_inactive: Option<i64>,
+    weekly_gexp: i64,
+    reasons: Vec<String>,
+}
+
+async fn handle_kicklist_command(
     http: &Arc<HttpClient>,
     config: Arc<Mutex<Config>>,
-    interaction: &Box<InteractionCreate>,
-    interaction_data: &Box<CommandData>,
+    interaction: &InteractionCreate,
+    interaction_data: &CommandData,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let eb;
-
-    let mut command = String::new();
+    let mut guild_name = String::new();
+    let mut csv = false;
     for opt in &interaction_data.options {
-        if opt.name == "command" {
+        if opt.name == "guild" {
             if let CommandOptionValue::String(opt_str) = &opt.value {
-                command = opt_str.to_lowercase();
+                guild_name = opt_str.to_string();
+            }
+        } else if opt.name == "csv" {
+            if let CommandOptionValue::Boolean(opt_bool) = &opt.value {
+                csv = *opt_bool;
             }
         }
     }
 
-    let mut config = config.lock().await;
-    let pool = config.database.get().await?;
-    let mut server_config = ServerConfig::read_config(&pool).await;
+    let (guild, pool, server_config) = {
+        let mut config = config.lock().await;
+        let pool = config.database.get().await?;
+        let server_config = ServerConfig::read_config(&pool).await;
+        let guild = config
+            .hypixel_api
+            .get_guild_by_name(&guild_name)
+            .await?
+            .guild
+            .ok_or("Invalid guild")?;
+        record_guild_exp(&pool, &guild).await?;
+        (guild, pool, server_config)
+    };
 
-    let cmd_args: Vec<_> = command.split(' ').collect();
-    if cmd_args.len() == 2 && cmd_args.first().unwrap() == &"verified_role" {
-        let verified_role =
-            Id::from_str(&cmd_args.get(1).unwrap().replace("<@&", "").replace('>', ""))?;
-        let mut found_role = false;
+    let guild_reqs = server_config.guild_reqs.get(&guild.name.to_lowercase());
+    let weekly_req = guild_reqs.map(|reqs| reqs.weekly_gexp).unwrap_or(0);
+    let day_millis = 24 * 60 * 60 * 1000;
+    let now = get_timestamp_millis();
 
-        let roles = http
-            .roles(interaction.guild_id.unwrap())
-            .exec()
-            .await?
-            .model()
-            .await?;
-        for role in roles {
-            if role.id == verified_role {
-                found_role = true;
-                break;
+    let member_uuids: Vec<&String> = guild
+        .members
+        .iter()
+        .map(|guild_member| &guild_member.uuid)
+        .collect();
+    let weekly_exp: HashMap<String, i64> = pool
+        .query(
+            "SELECT uuid, SUM(exp)::BIGINT AS weekly FROM guild_exp WHERE guild_id = $1 AND uuid = ANY($2) AND date::date > CURRENT_DATE - 7 GROUP BY uuid",
+            &[&guild.id, &member_uuids],
+        )
+        .await?
+        .iter()
+        .map(|db_res| (db_res.get("uuid"), db_res.get("weekly")))
+        .collect();
+    // Stats come from the hourly cache rather than fetching every profile
+    let cached_stats: HashMap<String, PlayerStats> = pool
+        .query(
+            "SELECT * FROM player_stats WHERE uuid = ANY($1)",
+            &[&member_uuids],
+        )
+        .await?
+        .iter()
+        .map(|db_res| (db_res.get("uuid"), PlayerStats::from_row(db_res)))
+        .collect();
+
+    let checked_uuids: Vec<String> = guild
+        .members
+        .iter()
+        .filter(|guild_member| {
+            !server_config.kick_exempt.contains_key(&guild_member.uuid)
+                && normalize_rank(&guild_member.rank) != "guild master"
+        })
+        .map(|guild_member| guild_member.uuid.clone())
+        .collect();
+    let (logins, skipped) = get_last_logins(&config, &pool, &checked_uuids).await?;
+
+    let mut entries = Vec::new();
+    for guild_member in &guild.members {
+        let login = match logins.get(&guild_member.uuid) {
+            Some(login) => login,
+            None => continue,
+        };
+        let days_inactive = login
+            .last_login
+            .map(|last_login| (now - last_login) / day_millis);
+        let weekly_gexp = weekly_exp.get(&guild_member.uuid).copied().unwrap_or(0);
+
+        let mut reasons = Vec::new();
+        if let Some(days_inactive) = days_inactive {
+            if days_inactive >= server_config.inactivity_days {
+                reasons.push(format!("Inactive for {} days", days_inactive));
             }
         }
-
-        if found_role {
-            server_config.verified_role = verified_role.to_string();
-            server_config.write_config(&pool).await;
-            eb = default_embed("Settings")
-                .description(format!("Set verified role to <@&{}>", verified_role));
-        } else {
-            eb = default_embed("Settings")
-                .description(format!("Invalid role: <@&{}>", verified_role));
+        if weekly_gexp < weekly_req && !is_new_member(guild_member.joined, now) {
+            reasons.push(format!("{}/{} weekly GEXP", weekly_gexp, weekly_req));
         }
-    } else if cmd_args.len() >= 3 && cmd_args.first().unwrap() == &"guild_role" {
-        let guild_role_raw = cmd_args.last().unwrap();
-        let guild_role = Id::from_str(&guild_role_raw.replace("<@&", "").replace('>', ""))?;
-        let guild_name = command
-            .split("guild_role ")
-            .last()
-            .unwrap()
-            .split(guild_role_raw)
-            .next()
-            .unwrap()
-            .trim();
-        let mut found_role = false;
-
-        for role in http
-            .roles(interaction.guild_id.unwrap())
-            .exec()
-            .await?
-            .model()
-            .await?
+        if let (Some(guild_reqs), Some(stats)) = (guild_reqs, cached_stats.get(&guild_member.uuid))
         {
-            if role.id == guild_role {
-                found_role = true;
+            let failing = guild_reqs.get_failing(stats);
+            if !failing.is_empty() {
+                reasons.push(format!("Failing {} requirements", failing.join(", ")));
             }
         }
 
-        if !found_role {
-            eb = default_embed("Settings").description(format!("Invalid role: <@&{}>", guild_role));
-        } else {
-            let guild = config
-                .hypixel_api
-                .get_guild_by_name(guild_name)
-                .await?
-                .guild
-                .ok_or("Invalid guild")?;
-            server_config
-                .guild_roles
-                .insert(guild.id, guild_role.to_string());
-            server_config.write_config(&pool).await;
-            eb = default_embed("Settings").description(format!(
-                "Set guild role for {} to <@&{}>",
-                guild.name, guild_role
-            ));
+        if !reasons.is_empty() {
+            entries.push(KicklistEntry {
+                uuid: guild_member.uuid.clone(),
+                username: login
+                    .username
+                    .clone()
+                    .unwrap_or_else(|| guild_member.uuid.clone()),
+                rank: guild_member.rank.clone(),
+                days_inactive,
+                weekly_gexp,
+                reasons,
+            });
         }
-    } else if cmd_args.len() == 1 && cmd_args.get(0).unwrap() == &"view" {
+    }
+    let skipped_note = if skipped > 0 {
+        format!("\n{} members could not be checked", skipped)
+    } else {
+        String::new()
+    };
+
+    entries.sort_by(|a, b| {
+        b.reasons
+            .len()
+            .cmp(&a.reasons.len())
+            .then(b.days_inactive.cmp(&a.days_inactive))
+            .then(a.weekly_gexp.cmp(&b.weekly_gexp))
+    });
+
+    let title = format!("Kick List for {}", guild.name);
+    if csv {
+        let mut out = String::from("username,uuid,rank,days_inactive,weekly_gexp,reasons");
+        for entry in &entries {
+            let fields = [
+                entry.username.clone(),
+                entry.uuid.clone(),
+                entry.rank.clone(),
+                entry
+                    .days_inactive
+                    .map(|days| days.to_string())
+                    .unwrap_or_default(),
+                entry.weekly_gexp.to_string(),
+                entry.reasons.join("; "),
+            ];
+            let row: Vec<String> = fields
+                .iter()
+                .map(|field| format!("\"{}\"", field.replace('"', "\"\"")))
+                .collect();
+            write!(out, "\n{}", row.join(","))?;
+        }
+
+        let _ = http
+            .interaction(SELF_USER_ID.lock().await.unwrap())
+            .create_followup(&interaction.token)
+            .embeds(&[default_embed(&title)
+                .description(format!("{} members flagged{}", entries.len(), skipped_note))
+                .build()])?
+            .attachments(&[Attachment::from_bytes(
+                format!("kicklist_{}.csv", guild.name.to_lowercase()),
+                out.into_bytes(),
+                0,
+            )])?
+            .exec()
+            .await?;
+    } else {
         let mut out = format!(
-            "Verified Role: <@&{}>\nGuild Roles:",
-            server_config.verified_role
+            "{} members flagged (inactivity threshold: {} days){}",
+            entries.len(),
+            server_config.inactivity_days,
+            skipped_note
         );
-        for guild_role in server_config.guild_roles {
-            let guild_res = config.hypixel_api.get_guild_by_id(&guild_role.0).await?;
-            write!(
-                out,
-                "\n  • {}: <@&{}>",
-                guild_res.guild.ok_or("Invalid guild")?.name,
-                guild_role.1
-            )?;
-        }
-        write!(out, "\nReqs:")?;
-        for guild_req in server_config.guild_reqs {
+        for (idx, entry) in entries.iter().enumerate() {
+            if idx == 25 {
+                write!(
+                    out,
+                    "\n\n{} more, use the csv option for the full list",
+                    entries.len() - idx
+                )?;
+                break;
+            }
+
             write!(
                 out,
-                "\n  • {}: slayer = {}, skills = {}, cata = {}, weight = {}",
-                guild_req.0,
-                guild_req.1.slayer,
-                guild_req.1.skills,
-                guild_req.1.catacombs,
-                guild_req.1.weight
+                "\n{}. [{}](https://mine.ly/{}) ({}): {}",
+                idx + 1,
+                entry.username,
+                entry.uuid,
+                entry.rank,
+                entry.reasons.join(", ")
             )?;
         }
-        eb = default_embed("Settings").description(out);
-    } else if cmd_args.len() >= 3 && cmd_args.get(0).unwrap() == &"reqs" {
-        let guild_name = cmd_args.get(2).unwrap();
-        if cmd_args.get(1).unwrap() == &"clear" {
-            server_config.guild_reqs.remove(&guild_name.to_string());
-            server_config.write_config(&pool).await;
-            eb = default_embed("Settings").description(format!("Cleared reqs for {}", guild_name));
-        } else if cmd_args.get(1).unwrap() == &"set" && cmd_args.len() == 5 {
-            let mut cur_reqs = server_config
-                .guild_reqs
-                .remove(&guild_name.to_string())
-                .unwrap_or_default();
-
-            let req_name = cmd_args.get(3).unwrap();
-            let req_amt: i64 = cmd_args.get(4).unwrap().parse()?;
 
-            let mut valid_req = true;
-            match cmd_args.get(3).unwrap() {
-                &"slayer" => cur_reqs.slayer = req_amt,
-                &"skills" => cur_reqs.skills = req_amt,
-                &"catacombs" => cur_reqs.catacombs = req_amt,
-                &"weight" => cur_reqs.weight = req_amt,
-                _ => valid_req = false,
-            };
+        let _ = http
+            .interaction(SELF_USER_ID.lock().await.unwrap())
+            .create_followup(&interaction.token)
+            .embeds(&[default_embed(&title).description(out).build()])?
+            .exec()
+            .await?;
+    }
 
-            if valid_req {
-                server_config
-                    .guild_reqs
-                    .insert(guild_name.to_string(), cur_reqs);
+    Ok(())
+}
 
-                server_config.write_config(&pool).await;
-                eb = default_embed("Settings").description(format!(
-                    "Set {} req to {} for {}",
-                    req_name, req_amt, guild_name
-                ));
-            } else {
-                eb = default_embed("Settings").description("Invalid requirement type")
+async fn handle_whois_command(
+    http: &Arc<HttpClient>,
+    config: Arc<Mutex<Config>>,
+    interaction: &InteractionCreate,
+    interaction_data: &CommandData,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut player = String::new();
+    for opt in &interaction_data.options {
+        if opt.name == "player" {
+            if let CommandOptionValue::String(opt_str) = &opt.value {
+                player = opt_str.trim().to_string();
             }
-        } else {
-            eb = default_embed("Settings").description("Invalid command");
         }
+    }
+
+    let config = config.lock().await;
+    let pool = config.database.get().await?;
+    let server_config = ServerConfig::read_config(&pool).await;
+    // Names can be reused after a rename, so resolve the current owner first and
+    // only trust stored names when the API lookup fails
+    let query_uuid = player.replace('-', "").to_lowercase();
+    let mut historical = false;
+    let (uuid, current_name) = if query_uuid.len() == 32
+        && query_uuid.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        let current_name = config
+            .hypixel_api
+            .uuid_to_username(&query_uuid)
+            .await
+            .ok()
+            .map(|uuid_res| uuid_res.username);
+        (query_uuid, current_name)
+    } else if let Ok(uuid_res) = config.hypixel_api.username_to_uuid(&player).await {
+        (uuid_res.uuid, Some(uuid_res.username))
     } else {
-        eb = default_embed("Settings").description("Invalid command");
+        historical = true;
+        let mut stored_res = pool
+            .query_opt(
+                "SELECT uuid FROM linked_accounts WHERE LOWER(username) = LOWER($1) LIMIT 1",
+                &[&player],
+            )
+            .await?;
+        if stored_res.is_none() {
+            stored_res = pool
+                .query_opt(
+                    "SELECT uuid FROM name_history WHERE LOWER(old_username) = LOWER($1) ORDER BY time DESC LIMIT 1",
+                    &[&player],
+                )
+                .await?;
+        }
+        (stored_res.ok_or("Player is not linked")?.get("uuid"), None)
+    };
+    let db_res_vec = pool
+        .query("SELECT * FROM linked_accounts WHERE uuid = $1", &[&uuid])
+        .await?;
+    let db_res = db_res_vec.first().ok_or("Player is not linked")?;
+
+    let account = LinkedAccount::from_row(db_res);
+    let last_updated: i64 = db_res.get("last_updated");
+    let mut out = format!(
+        "[{}](https://mine.ly/{}) is linked to <@{}>",
+        current_name.as_ref().unwrap_or(&account.username),
+        account.uuid,
+        account.discord
+    );
+    if let Some(current_name) = current_name {
+        if let Some(old_username) = refresh_username(
+            http,
+            &config,
+            &pool,
+            &server_config,
+            &account.uuid,
+            &current_name,
+        )
+        .await?
+        {
+            write!(out, "\nPreviously known as: {}", old_username)?;
+        }
+    }
+    write!(
+        out,
+        "\nUUID: {}\nPrimary account: {}\nLinked: <t:{}:f>",
+        account.uuid,
+        account.is_primary,
+        last_updated / 1000
+    )?;
+    if historical {
+        write!(
+            out,
+            "\nFound by a previously stored name, it may now belong to a different player"
+        )?;
     }
 
     let _ = http
         .interaction(SELF_USER_ID.lock().await.unwrap())
         .create_followup(&interaction.token)
-        .embeds(&[eb.build()])?
+        .embeds(&[default_embed("Player Information").description(out).build()])?
         .exec()
         .await?;
 
     Ok(())
 }
 
-async fn handle_user_command(
+async fn handle_history_command(
     http: &Arc<HttpClient>,
     config: Arc<Mutex<Config>>,
-    interaction: &Box<InteractionCreate>,
-    interaction_data: &Box<CommandData>,
+    interaction: &InteractionCreate,
+    interaction_data: &CommandData,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let mut user = &Id::from_str("1")?;
+    let mut user = None;
+    let mut player = None;
     for opt in &interaction_data.options {
         if opt.name == "user" {
             if let CommandOptionValue::User(opt_user) = &opt.value {
-                user = opt_user;
+                user = Some(opt_user.to_string());
+            }
+        } else if opt.name == "player" {
+            if let CommandOptionValue::String(opt_str) = &opt.value {
+                player = Some(opt_str.to_string());
             }
         }
     }
 
     let config = config.lock().await;
     let pool = config.database.get().await?;
-    let db_res_vec = pool
-        .query(
-            "SELECT * FROM linked_accounts WHERE discord = $1",
-            &[&user.to_string()],
+    let (title, db_res_vec) = if let Some(user) = user {
+        (
+            format!("<@{}>", user),
+            pool.query(
+                "SELECT * FROM link_history WHERE discord = $1 ORDER BY time DESC LIMIT 20",
+                &[&user],
+            )
+            .await?,
         )
-        .await?;
-    let db_res = db_res_vec.get(0).ok_or("User is not linked")?;
+    } else if let Some(player) = player {
+        let uuid = config.hypixel_api.username_to_uuid(&player).await?.uuid;
+        (
+            player,
+            pool.query(
+                "SELECT * FROM link_history WHERE uuid = $1 ORDER BY time DESC LIMIT 20",
+                &[&uuid],
+            )
+            .await?,
+        )
+    } else {
+        return Err("Provide a user or a player".into());
+    };
 
-    let username: String = db_res.get("username");
-    let uuid: String = db_res.get("uuid");
+    let mut out = format!("Link history for {}:", title);
+    if db_res_vec.is_empty() {
+        write!(out, "\nNo history found")?;
+    }
+    for db_res in db_res_vec {
+        let account = LinkedAccount::from_row(&db_res);
+        let action: String = db_res.get("action");
+        let time: i64 = db_res.get("time");
+        write!(
+            out,
+            "\n<t:{}:d> {} <@{}> {} [{}](https://mine.ly/{})",
+            time / 1000,
+            if action == "link" {
+                "Linked"
+            } else {
+                "Unlinked"
+            },
+            account.discord,
+            if action == "link" { "to" } else { "from" },
+            account.username,
+            account.uuid
+        )?;
+    }
 
     let _ = http
         .interaction(SELF_USER_ID.lock().await.unwrap())
         .create_followup(&interaction.token)
-        .embeds(&[default_embed("User Information")
-            .description(format!(
-                "<@{}> is linked to [{}](https://mine.ly/{})",
-                user, username, uuid
-            ))
-            .build()])?
+        .embeds(&[default_embed("Link History").description(out).build()])?
         .exec()
         .await?;
 
@@ -607,14 +5761,51 @@ async fn handle_help_command(
         .embeds(&[default_embed("Help")
             .description(
                 "`/help`
-                `/verify <player>`
-                `/reqs <player> <profile>`
+                `/verify <player> [alt] [primary]`
+                `/primary <player>`
+                `/reqs <player> [profile]`
+                `/compare <player1> <player2> [group]`
+                `/apply <guild>`
+                `/waitlist [guild]`
+                `/roles update [@user]`
+                `/leaderboard <stat> [guild]`
+                `/progress <player> [days]`
+                `/competition start <stat> <duration> [guild]`
+                `/competition leaderboard`
+                `/competition end`
                 `/user <@user>`
+                `/reconcile`
+                `/whois <player|uuid>`
+                `/guild <name|player>`
+                `/status <player>`
+                `/online <name|player>`
+                `/gexp <guild|player>`
+                `/kicklist <guild> [csv]`
+                `/warnings [guild]`
+                `/history [@user] [player]`
                 `/settings view`
                 `/settings verified_role <@role>`
+                `/settings unverified_role <@role>`
+                `/settings verify_panel <#channel>`
                 `/settings guild_role <guild> <@role>`
+                `/settings rank_role <guild>, <rank> <@role>`
+                `/settings rank_role clear <guild>`
                 `/settings reqs remove <guild> type`
-                `/settings reqs set <guild> <type> <amount>`",
+                `/settings reqs set <guild> <type> <amount>`
+                `/settings sync_nickname <true|false>`
+                `/settings rejoin_reqs <true|false>`
+                `/settings leave_policy <keep|archive|delete>`
+                `/settings log_channel <#channel>`
+                `/settings guild_log_channel <#channel>`
+                `/settings stat_role <stat> <threshold> <@role>`
+                `/settings stat_role remove <@role>`
+                `/settings sweep_channel <#channel>`
+                `/settings application_channel <#channel>`
+                `/settings grace_days <days>`
+                `/settings announce_renames <true|false>`
+                `/settings inactivity_days <days>`
+                `/settings kick_exempt add <player> [reason]`
+                `/settings kick_exempt remove <player>`",
             )
             .build()])?
         .exec()
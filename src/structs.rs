@@ -1,16 +1,86 @@
 use deadpool_postgres::Object;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use twilight_model::application::command::{BaseCommandOptionData, ChoiceCommandOptionData};
+use tokio_postgres::Row;
+use twilight_model::application::command::{
+    BaseCommandOptionData, ChoiceCommandOptionData, CommandOptionChoice, OptionsCommandOptionData,
+};
 
 #[derive(Deserialize, Serialize)]
 pub struct ServerConfig {
     #[serde(default = "Default::default")]
     pub verified_role: String,
     #[serde(default = "Default::default")]
+    pub unverified_role: String,
+    #[serde(default = "Default::default")]
     pub guild_roles: HashMap<String, String>,
     #[serde(default = "Default::default")]
     pub guild_reqs: HashMap<String, GuildReqs>,
+    /// Hypixel guild id to a map of lowercase rank name to role id
+    #[serde(default = "Default::default")]
+    pub rank_roles: HashMap<String, HashMap<String, String>>,
+    #[serde(default = "Default::default")]
+    pub sync_nickname: bool,
+    #[serde(default = "Default::default")]
+    pub rejoin_reqs: bool,
+    #[serde(default = "Default::default")]
+    pub leave_policy: LeavePolicy,
+    #[serde(default = "Default::default")]
+    pub log_channel: String,
+    #[serde(default = "Default::default")]
+    pub announce_renames: bool,
+    #[serde(default = "Default::default")]
+    pub guild_log_channel: String,
+    #[serde(default = "default_inactivity_days")]
+    pub inactivity_days: i64,
+    #[serde(default = "Default::default")]
+    pub sweep_channel: String,
+    #[serde(default = "Default::default")]
+    pub application_channel: String,
+    #[serde(default = "default_grace_days")]
+    pub grace_days: i64,
+    /// Role id to the stat threshold that grants it
+    #[serde(default = "Default::default")]
+    pub stat_roles: HashMap<String, StatRole>,
+    /// Uuid to the reason the member is exempt from the kick list
+    #[serde(default = "Default::default")]
+    pub kick_exempt: HashMap<String, String>,
+}
+
+fn default_inactivity_days() -> i64 {
+    14
+}
+
+fn default_grace_days() -> i64 {
+    7
+}
+
+#[derive(Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LeavePolicy {
+    #[default]
+    Keep,
+    Archive,
+    Delete,
+}
+
+impl LeavePolicy {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "keep" => Some(LeavePolicy::Keep),
+            "archive" => Some(LeavePolicy::Archive),
+            "delete" => Some(LeavePolicy::Delete),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LeavePolicy::Keep => "keep",
+            LeavePolicy::Archive => "archive",
+            LeavePolicy::Delete => "delete",
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize)]
@@ -19,6 +89,8 @@ pub struct GuildReqs {
     pub skills: i64,
     pub catacombs: i64,
     pub weight: i64,
+    #[serde(default = "Default::default")]
+    pub weekly_gexp: i64,
 }
 
 impl Default for GuildReqs {
@@ -28,7 +100,115 @@ impl Default for GuildReqs {
             skills: 0,
             catacombs: 0,
             weight: 0,
+            weekly_gexp: 0,
+        }
+    }
+}
+
+impl GuildReqs {
+    pub fn is_met(&self, stats: &PlayerStats) -> bool {
+        self.get_failing(stats).is_empty()
+    }
+
+    /// Names of the stat requirements the player does not meet
+    pub fn get_failing(&self, stats: &PlayerStats) -> Vec<&'static str> {
+        let mut failing = Vec::new();
+        if stats.slayer < self.slayer {
+            failing.push("slayer");
+        }
+        if stats.skills < self.skills as f64 {
+            failing.push("skills");
         }
+        if stats.catacombs < self.catacombs as f64 {
+            failing.push("catacombs");
+        }
+        if stats.weight < self.weight as f64 {
+            failing.push("weight");
+        }
+        failing
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct StatRole {
+    pub stat: String,
+    pub threshold: f64,
+}
+
+#[derive(Clone)]
+pub struct PlayerStats {
+    pub slayer: i64,
+    pub skills: f64,
+    pub catacombs: f64,
+    pub weight: f64,
+    pub level: f64,
+}
+
+impl PlayerStats {
+    pub const NAMES: [&'static str; 5] = ["slayer", "skills", "catacombs", "weight", "level"];
+
+    /// Reads a row of the `player_stats` or `stat_snapshots` table
+    pub fn from_row(row: &Row) -> Self {
+        Self {
+            slayer: row.get("slayer"),
+            skills: row.get("skills"),
+            catacombs: row.get("catacombs"),
+            weight: row.get("weight"),
+            level: row.get("level"),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<f64> {
+        match name {
+            "slayer" => Some(self.slayer as f64),
+            "skills" => Some(self.skills),
+            "catacombs" => Some(self.catacombs),
+            "weight" => Some(self.weight),
+            "level" => Some(self.level),
+            _ => None,
+        }
+    }
+}
+
+pub struct PlayerLogin {
+    pub username: Option<String>,
+    pub last_login: Option<i64>,
+}
+
+impl PlayerLogin {
+    pub fn from_row(row: &Row) -> Self {
+        Self {
+            username: row.get("username"),
+            last_login: row.get("last_login"),
+        }
+    }
+}
+
+pub struct PlayerStatus {
+    pub online: bool,
+    pub game: Option<String>,
+    pub mode: Option<String>,
+    pub map: Option<String>,
+}
+
+impl PlayerStatus {
+    pub fn from_row(row: &Row) -> Self {
+        Self {
+            online: row.get("online"),
+            game: row.get("game"),
+            mode: row.get("mode"),
+            map: row.get("map"),
+        }
+    }
+
+    /// Game, mode and map joined for display
+    pub fn get_location(&self) -> String {
+        [&self.game, &self.mode, &self.map]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect::<Vec<String>>()
+            .join(" - ")
     }
 }
 
@@ -49,6 +229,55 @@ impl ServerConfig {
     }
 }
 
+#[derive(Clone)]
+pub struct LinkedAccount {
+    pub uuid: String,
+    pub username: String,
+    pub discord: String,
+    pub is_primary: bool,
+}
+
+impl LinkedAccount {
+    pub fn from_row(row: &Row) -> Self {
+        Self {
+            uuid: row.get("uuid"),
+            username: row.get("username"),
+            discord: row.get("discord"),
+            is_primary: row.try_get("is_primary").unwrap_or(true),
+        }
+    }
+
+    /// All accounts linked to a Discord user with the primary account first
+    pub async fn get_by_discord(
+        pool: &Object,
+        discord: &str,
+    ) -> Result<Vec<Self>, tokio_postgres::Error> {
+        Ok(pool
+            .query(
+                "SELECT * FROM linked_accounts WHERE discord = $1 ORDER BY is_primary DESC, last_updated",
+                &[&discord],
+            )
+            .await?
+            .iter()
+            .map(Self::from_row)
+            .collect())
+    }
+
+    pub async fn record_history(
+        &self,
+        pool: &Object,
+        action: &str,
+        time: i64,
+    ) -> Result<(), tokio_postgres::Error> {
+        pool.query(
+            "INSERT INTO link_history (uuid, username, discord, action, time) VALUES ($1, $2, $3, $4, $5)",
+            &[&self.uuid, &self.username, &self.discord, &action, &time],
+        )
+        .await?;
+        Ok(())
+    }
+}
+
 pub struct DiscordInfo {
     pub username: Option<String>,
     pub uuid: Option<String>,
@@ -88,6 +317,15 @@ impl CommandOptionBuilder {
         self.0.required = required;
         self
     }
+
+    pub fn add_choice(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.0.choices.push(CommandOptionChoice::String {
+            name: name.into(),
+            name_localizations: None,
+            value: value.into(),
+        });
+        self
+    }
 }
 
 impl Into<ChoiceCommandOptionData> for CommandOptionBuilder {
@@ -96,6 +334,18 @@ impl Into<ChoiceCommandOptionData> for CommandOptionBuilder {
     }
 }
 
+impl From<CommandOptionBuilder> for OptionsCommandOptionData {
+    fn from(builder: CommandOptionBuilder) -> Self {
+        OptionsCommandOptionData {
+            description: builder.0.description,
+            description_localizations: builder.0.description_localizations,
+            name: builder.0.name,
+            name_localizations: builder.0.name_localizations,
+            options: Vec::new(),
+        }
+    }
+}
+
 impl Into<BaseCommandOptionData> for CommandOptionBuilder {
     fn into(self) -> BaseCommandOptionData {
         BaseCommandOptionData {
@@ -1,16 +1,60 @@
 use lazy_static::lazy_static;
-use rs_pixel::util::generic_json::Property;
+use rs_pixel::util::{
+    generic_json::Property,
+    skyblock_profile::{LevelingStruct, SkyblockProfile},
+};
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::{Mutex, MutexGuard};
 use twilight_model::id::{marker::ApplicationMarker, Id};
 use twilight_util::builder::embed::EmbedBuilder;
 
-use crate::{config::Config, structs::DiscordInfo};
+use crate::{
+    config::Config,
+    structs::{DiscordInfo, PlayerStats},
+};
 
 lazy_static! {
     pub static ref SELF_USER_ID: Mutex<Option<Id<ApplicationMarker>>> = Mutex::new(None);
 }
 
+pub const GUILD_MEMBER_LIMIT: usize = 125;
+// Guild exp needed for each of the first levels, every level after needs the last amount
+const GUILD_LEVEL_EXP: [i64; 15] = [
+    100_000, 150_000, 250_000, 500_000, 750_000, 1_000_000, 1_250_000, 1_500_000, 2_000_000,
+    2_500_000, 2_500_000, 2_500_000, 2_500_000, 2_500_000, 3_000_000,
+];
+
+// Senither weight constants: (name, exponent, divider, weight max level)
+const SKILL_WEIGHTS: [(&str, f64, f64, i64); 8] = [
+    ("mining", 1.182_074_48, 259_634.0, 60),
+    ("foraging", 1.232_826, 259_634.0, 50),
+    ("enchanting", 0.969_765_83, 882_758.0, 60),
+    ("farming", 1.217_848_139, 220_689.0, 60),
+    ("combat", 1.157_976_872_65, 275_862.0, 60),
+    ("fishing", 1.406_418, 88_274.0, 50),
+    ("alchemy", 1.0, 1_103_448.0, 50),
+    ("taming", 1.147_44, 441_379.0, 50),
+];
+// (name, divider, modifier)
+const SLAYER_WEIGHTS: [(&str, f64, f64); 4] = [
+    ("zombie", 2208.0, 0.15),
+    ("spider", 2118.0, 0.08),
+    ("wolf", 1962.0, 0.015),
+    ("enderman", 1430.0, 0.017),
+];
+// (name, multiplier)
+const DUNGEON_WEIGHTS: [(&str, f64); 5] = [
+    ("healer", 0.000_004_525_483_4),
+    ("mage", 0.000_004_525_483_4),
+    ("berserk", 0.000_004_525_483_4),
+    ("archer", 0.000_004_525_483_4),
+    ("tank", 0.000_004_525_483_4),
+];
+const CATACOMBS_WEIGHT: f64 = 0.000_214_960_461_5;
+const SKILL_50_EXP: i64 = 55_172_425;
+const SKILL_60_EXP: i64 = 111_672_425;
+const DUNGEON_50_EXP: i64 = 569_809_640;
+
 pub fn get_timestamp_millis() -> i64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -38,6 +82,219 @@ pub async fn get_discord_info(config: &mut MutexGuard<'_, Config>, player: Strin
     }
 }
 
+/// Lowercases a Hypixel guild rank, the guild master rank is sometimes returned as `GUILDMASTER`
+pub fn normalize_rank(rank: &str) -> String {
+    let rank = rank.trim().to_lowercase();
+    if rank == "guildmaster" {
+        "guild master".to_string()
+    } else {
+        rank
+    }
+}
+
+/// Stats a competition can track, the skill names track skill experience
+pub fn get_competition_stats() -> Vec<&'static str> {
+    PlayerStats::NAMES
+        .into_iter()
+        .chain(get_skill_names())
+        .collect()
+}
+
+/// Skills that count towards the skill average
+pub fn get_skill_names() -> Vec<&'static str> {
+    SKILL_WEIGHTS.iter().map(|(name, _, _, _)| *name).collect()
+}
+
+pub fn get_skill_level(profile: &SkyblockProfile, skill_name: &str) -> f64 {
+    profile
+        .get_skill(skill_name)
+        .map_or(0.0, |skill| get_level_with_progress(&skill))
+}
+
+pub fn get_dungeon_levels(profile: &SkyblockProfile) -> Vec<(&'static str, f64)> {
+    let mut levels = vec![(
+        "catacombs",
+        profile
+            .get_catacombs()
+            .map_or(0.0, |cata| get_level_with_progress(&cata)),
+    )];
+    for (name, _) in DUNGEON_WEIGHTS {
+        levels.push((
+            name,
+            profile
+                .get_dungeon_class(name)
+                .map_or(0.0, |class| get_level_with_progress(&class)),
+        ));
+    }
+    levels
+}
+
+/// Reads a competition stat from a profile, see [`get_competition_stats`]
+pub fn get_competition_value(profile: &SkyblockProfile, stat: &str) -> Option<f64> {
+    match get_player_stats(profile).get(stat) {
+        Some(value) => Some(value),
+        None => profile.get_skill(stat).map(|skill| skill.total_exp as f64),
+    }
+}
+
+const MAX_DURATION_MILLIS: i64 = 52 * 7 * 24 * 60 * 60 * 1000;
+
+/// Parses durations such as `30m`, `12h`, `7d` or `2w` into milliseconds, up to 52 weeks
+pub fn parse_duration(duration: &str) -> Option<i64> {
+    let duration = duration.trim().to_lowercase();
+    let unit = match duration.chars().last()? {
+        'm' => 60 * 1000,
+        'h' => 60 * 60 * 1000,
+        'd' => 24 * 60 * 60 * 1000,
+        'w' => 7 * 24 * 60 * 60 * 1000,
+        _ => return None,
+    };
+    duration[..duration.len() - 1]
+        .parse::<i64>()
+        .ok()
+        .filter(|amount| *amount > 0)
+        .and_then(|amount| amount.checked_mul(unit))
+        .filter(|duration| *duration <= MAX_DURATION_MILLIS)
+}
+
+pub fn get_guild_level(exp: i64) -> f64 {
+    let mut exp = exp;
+    let mut level = 0.0;
+    for (idx, needed) in GUILD_LEVEL_EXP.iter().enumerate() {
+        if exp < *needed {
+            return level + exp as f64 / *needed as f64;
+        }
+        exp -= needed;
+        level = (idx + 1) as f64;
+    }
+
+    let last = GUILD_LEVEL_EXP[GUILD_LEVEL_EXP.len() - 1];
+    level + exp as f64 / last as f64
+}
+
+/// Formats a timestamp as `YYYY-MM-DD` in UTC
+pub fn format_date(millis: i64) -> String {
+    // Days to civil date from https://howardhinnant.github.io/date_algorithms.html
+    let days = millis.div_euclid(24 * 60 * 60 * 1000) + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Parses amounts such as `40`, `2.5k` or `10m`
+pub fn parse_amount(amount: &str) -> Option<f64> {
+    let amount = amount.trim().to_lowercase();
+    let (number, multiplier) = match amount.chars().last()? {
+        'k' => (&amount[..amount.len() - 1], 1_000.0),
+        'm' => (&amount[..amount.len() - 1], 1_000_000.0),
+        'b' => (&amount[..amount.len() - 1], 1_000_000_000.0),
+        _ => (amount.as_str(), 1.0),
+    };
+    number.parse::<f64>().ok().map(|number| number * multiplier)
+}
+
 pub fn default_embed(title: &str) -> EmbedBuilder {
     EmbedBuilder::new().title(title)
 }
+
+fn get_level_with_progress(leveling: &LevelingStruct) -> f64 {
+    leveling.level as f64 + leveling.progress_to_next
+}
+
+fn get_skill_weight(leveling: &LevelingStruct, exponent: f64, divider: f64, max_level: i64) -> f64 {
+    let max_exp = if max_level == 60 {
+        SKILL_60_EXP
+    } else {
+        SKILL_50_EXP
+    };
+    let level = get_level_with_progress(leveling).min(max_level as f64);
+    let base = (level * 10.0).powf(0.5 + exponent + level / 100.0) / 1250.0;
+
+    if leveling.total_exp > max_exp {
+        base.round() + ((leveling.total_exp - max_exp) as f64 / divider).powf(0.968)
+    } else {
+        base
+    }
+}
+
+fn get_slayer_weight(exp: i64, divider: f64, modifier: f64) -> f64 {
+    if exp <= 1_000_000 {
+        return exp as f64 / divider;
+    }
+
+    let base = 1_000_000.0 / divider;
+    let mut remaining = exp - 1_000_000;
+    let mut cur_modifier = modifier;
+    let mut overflow = 0.0;
+    while remaining > 0 {
+        let left = remaining.min(1_000_000);
+        overflow += (left as f64 / (divider * (1.5 + cur_modifier))).powf(0.942);
+        cur_modifier += modifier;
+        remaining -= left;
+    }
+
+    base + overflow
+}
+
+fn get_dungeon_weight(leveling: &LevelingStruct, multiplier: f64) -> f64 {
+    let base = get_level_with_progress(leveling).powf(4.5) * multiplier;
+
+    if leveling.total_exp > DUNGEON_50_EXP {
+        let splitter = (4 * DUNGEON_50_EXP) as f64 / base;
+        base.floor() + ((leveling.total_exp - DUNGEON_50_EXP) as f64 / splitter).powf(0.968)
+    } else {
+        base
+    }
+}
+
+pub fn get_player_stats(profile: &SkyblockProfile) -> PlayerStats {
+    let mut weight = 0.0;
+
+    let mut skills_total = 0.0;
+    for (name, exponent, divider, max_level) in SKILL_WEIGHTS {
+        if let Some(skill) = profile.get_skill(name) {
+            skills_total += get_level_with_progress(&skill);
+            weight += get_skill_weight(&skill, exponent, divider, max_level);
+        }
+    }
+
+    let mut slayer = 0;
+    for (name, divider, modifier) in SLAYER_WEIGHTS {
+        if let Some(slayer_boss) = profile.get_slayer(name) {
+            slayer += slayer_boss.total_exp;
+            weight += get_slayer_weight(slayer_boss.total_exp, divider, modifier);
+        }
+    }
+    if let Some(blaze) = profile.get_slayer("blaze") {
+        slayer += blaze.total_exp;
+    }
+
+    let mut catacombs = 0.0;
+    if let Some(cata) = profile.get_catacombs() {
+        catacombs = get_level_with_progress(&cata);
+        weight += get_dungeon_weight(&cata, CATACOMBS_WEIGHT);
+    }
+    for (name, multiplier) in DUNGEON_WEIGHTS {
+        if let Some(class) = profile.get_dungeon_class(name) {
+            weight += get_dungeon_weight(&class, multiplier);
+        }
+    }
+
+    PlayerStats {
+        slayer,
+        skills: skills_total / SKILL_WEIGHTS.len() as f64,
+        catacombs,
+        weight,
+        level: profile
+            .get_player_int_property("leveling.experience")
+            .unwrap_or(0) as f64
+            / 100.0,
+    }
+}